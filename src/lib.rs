@@ -1,28 +1,379 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
 use wasm_bindgen::prelude::*;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 
 // Grid configuration
-const GRID_SIZE: u32 = 256; // 256x256 = 65536 for BMP
+const GRID_SIZE: u32 = 256; // Default columns: 256x256 = 65536 per plane
 const CELL_SIZE: f64 = 20.0; // Base cell size at zoom 1.0
+const MAX_CODEPOINT: u32 = 0x10FFFF;
+const WHEEL_ZOOM_STEP: f64 = 1.1; // Zoom factor per wheel event
+const BUTTON_ZOOM_STEP: f64 = std::f64::consts::SQRT_2; // Zoom factor per +/- step
+
+const DEFAULT_BACKGROUND: &str = "#1a1a2e";
+const DEFAULT_SELECTION_COLOR: &str = "#ffcc00";
+const DEFAULT_SELECTION_WIDTH: f64 = 3.0;
+const MAX_SELECTION_WIDTH: f64 = 12.0;
+const NAME_LABEL_ZOOM: f64 = 3.0; // Minimum zoom for name labels
+const HEX_LABEL_ZOOM: f64 = 1.0; // Minimum zoom for hex labels
+const GLYPH_MIN_ZOOM: f64 = 0.5; // Below this, cells are color only
+const FILTER_DIM_ALPHA: f64 = 0.75; // Background laid over cells failing the filter
+const SNAP_MAX_DISTANCE: u32 = 0x400; // Furthest `nearest_assigned` looks either way
+const PAN_MIN_VISIBLE: f64 = 64.0; // Grid kept on screen with pan bounds, in CSS pixels
+const HISTORY_LIMIT: usize = 100; // Selections remembered for back/forward
+const MAX_CSV_ROWS: u32 = 0x10000; // One plane per `export_range_csv` call
+const DEFAULT_CELL_PADDING: f64 = 1.0; // Inset of each cell's fill, in CSS pixels
+const DEFAULT_MARK_BASE: char = '\u{25CC}'; // DOTTED CIRCLE
+const DEFAULT_FONT_FAMILY: &str = "sans-serif";
+const AXIS_FONT_SIZE: f64 = 11.0;
+const AXIS_BAND_TOP: f64 = 18.0; // Height of the column header band
+const AXIS_BAND_LEFT: f64 = 52.0; // Width of the row header band, fits "10FF00"
+const NOTDEF_PROBE: &str = "\u{0378}"; // Unassigned, so any font draws its .notdef box
+
+// Category group colors
+const COLOR_LETTERS: &str = "#2d5a27"; // Green
+const COLOR_NUMBERS: &str = "#1a4d7a"; // Blue
+const COLOR_WHITESPACE: &str = "#4a4a4a"; // Gray
+const COLOR_CONTROL: &str = "#5a1a1a"; // Dark red
+const COLOR_PUNCTUATION: &str = "#6b4c1a"; // Brown
+const COLOR_SYMBOLS: &str = "#5a1a5a"; // Purple
+const COLOR_MARKS: &str = "#1a5a5a"; // Teal
+const COLOR_UNASSIGNED: &str = "#2a2a2a"; // Dark
+
+// Script palette; each script maps to a fixed slot via its ISO 15924 tag
+const SCRIPT_PALETTE: [&str; 16] = [
+    "#2d5a27", "#1a4d7a", "#6b4c1a", "#5a1a5a", "#1a5a5a", "#5a1a1a", "#4d5a1a", "#1a2d5a",
+    "#5a3a1a", "#3a1a5a", "#1a5a3a", "#5a1a3a", "#3a5a1a", "#1a3a5a", "#5a4a3a", "#3a3a6b",
+];
+const COLOR_SCRIPT_COMMON: &str = "#3a3a3a"; // Muted gray
+const COLOR_SCRIPT_INHERITED: &str = "#3a4040"; // Muted slate
+
+// UTF-8 length heatmap, from 1-byte (ASCII) to 4-byte sequences
+const UTF8_LENGTH_PALETTE: [&str; 4] = ["#1a4d7a", "#2d5a27", "#6b4c1a", "#5a1a1a"];
+
+// Age gradient endpoints, blended across every version in `AGE_VERSIONS`
+const AGE_COLOR_OLDEST: (u8, u8, u8) = (0x1a, 0x3a, 0x6b); // Deep blue
+const AGE_COLOR_NEWEST: (u8, u8, u8) = (0x8a, 0x2a, 0x3a); // Crimson
+
+// Unicode versions that introduced characters, oldest first. 1.0 characters
+// are reported as 1.1 by the UCD, so the list starts there.
+const AGE_VERSIONS: [(u16, u16); 19] = [
+    (1, 1), (2, 0), (2, 1), (3, 0), (3, 1), (3, 2), (4, 0), (4, 1), (5, 0), (5, 1),
+    (5, 2), (6, 0), (6, 1), (6, 2), (6, 3), (7, 0), (8, 0), (9, 0), (10, 0),
+];
+
+// Numeric values outside Nd that the UCD tables here don't carry, as
+// (first, last, numerator of first, denominator). Numerators count up by one
+// across a range. Covers the common fractions, super/subscripts, Roman
+// numerals, enclosed numbers, Hangzhou numerals and the common CJK numeral
+// ideographs (kPrimaryNumeric).
+const NUMERIC_VALUES: [(u32, u32, u32, u32); 76] = [
+    (0x00B2, 0x00B3, 2, 1), (0x00B9, 0x00B9, 1, 1),
+    (0x00BC, 0x00BC, 1, 4), (0x00BD, 0x00BD, 1, 2), (0x00BE, 0x00BE, 3, 4),
+    (0x2070, 0x2070, 0, 1), (0x2074, 0x2079, 4, 1), (0x2080, 0x2089, 0, 1),
+    (0x2150, 0x2150, 1, 7), (0x2151, 0x2151, 1, 9), (0x2152, 0x2152, 1, 10),
+    (0x2153, 0x2153, 1, 3), (0x2154, 0x2154, 2, 3), (0x2155, 0x2155, 1, 5),
+    (0x2156, 0x2156, 2, 5), (0x2157, 0x2157, 3, 5), (0x2158, 0x2158, 4, 5),
+    (0x2159, 0x2159, 1, 6), (0x215A, 0x215A, 5, 6), (0x215B, 0x215B, 1, 8),
+    (0x215C, 0x215C, 3, 8), (0x215D, 0x215D, 5, 8), (0x215E, 0x215E, 7, 8),
+    (0x215F, 0x215F, 1, 1),
+    (0x2160, 0x216B, 1, 1), (0x216C, 0x216C, 50, 1), (0x216D, 0x216D, 100, 1),
+    (0x216E, 0x216E, 500, 1), (0x216F, 0x216F, 1000, 1),
+    (0x2170, 0x217B, 1, 1), (0x217C, 0x217C, 50, 1), (0x217D, 0x217D, 100, 1),
+    (0x217E, 0x217E, 500, 1), (0x217F, 0x217F, 1000, 1), (0x2180, 0x2180, 1000, 1),
+    (0x2181, 0x2181, 5000, 1),
+    (0x2182, 0x2182, 10000, 1), (0x2185, 0x2185, 6, 1), (0x2186, 0x2186, 50, 1),
+    (0x2187, 0x2187, 50000, 1), (0x2188, 0x2188, 100000, 1), (0x2189, 0x2189, 0, 1),
+    (0x2460, 0x2473, 1, 1), (0x2474, 0x2487, 1, 1), (0x2488, 0x249B, 1, 1),
+    (0x24EA, 0x24EA, 0, 1), (0x24EB, 0x24F4, 11, 1), (0x24F5, 0x24FE, 1, 1),
+    (0x24FF, 0x24FF, 0, 1), (0x2776, 0x277F, 1, 1), (0x2780, 0x2789, 1, 1),
+    (0x278A, 0x2793, 1, 1),
+    (0x3007, 0x3007, 0, 1), (0x3021, 0x3029, 1, 1), (0x3038, 0x3038, 10, 1),
+    (0x3039, 0x3039, 20, 1), (0x303A, 0x303A, 30, 1),
+    (0x96F6, 0x96F6, 0, 1), (0x4E00, 0x4E00, 1, 1), (0x4E8C, 0x4E8C, 2, 1),
+    (0x4E09, 0x4E09, 3, 1), (0x56DB, 0x56DB, 4, 1), (0x4E94, 0x4E94, 5, 1),
+    (0x516D, 0x516D, 6, 1), (0x4E03, 0x4E03, 7, 1), (0x516B, 0x516B, 8, 1),
+    (0x4E5D, 0x4E5D, 9, 1), (0x5341, 0x5341, 10, 1), (0x767E, 0x767E, 100, 1),
+    (0x5343, 0x5343, 1000, 1), (0x4E07, 0x4E07, 10000, 1), (0x5104, 0x5104, 100000000, 1),
+    (0x2CFD, 0x2CFD, 1, 2), (0x0F2A, 0x0F2A, 1, 2), (0x0BF0, 0x0BF0, 10, 1),
+    (0x0BF1, 0x0BF1, 100, 1),
+];
+
+// Unicode version of `EAST_ASIAN_WIDTHS`. No unic-ucd 0.9 crate carries the
+// property, so this table tracks a newer release than the core UCD tables
+// (see `unicode_version`) and `data_versions` reports it on its own.
+const EAST_ASIAN_WIDTH_VERSION: (u64, u64, u64) = (14, 0, 0);
+
+// East_Asian_Width ranges, sorted and non-overlapping. Codepoints not listed
+// are Neutral ("N"); unassigned CJK ideograph ranges already carry their
+// default "W". Generated from EastAsianWidth.txt as shipped in Python 3.11's
+// `unicodedata` (Unicode 14.0.0): walk U+0000..U+10FFFF, give unassigned
+// codepoints the file's @missing default instead of `east_asian_width`'s
+// answer, merge runs of equal values and drop the "N" runs. To move to another
+// release, rerun that over its data and bump `EAST_ASIAN_WIDTH_VERSION`.
+const EAST_ASIAN_WIDTHS: [(u32, u32, &str); 315] = [
+    (0x0020, 0x007E, "Na"), (0x00A1, 0x00A1, "A"), (0x00A2, 0x00A3, "Na"),
+    (0x00A4, 0x00A4, "A"), (0x00A5, 0x00A6, "Na"), (0x00A7, 0x00A8, "A"), (0x00AA, 0x00AA, "A"),
+    (0x00AC, 0x00AC, "Na"), (0x00AD, 0x00AE, "A"), (0x00AF, 0x00AF, "Na"),
+    (0x00B0, 0x00B4, "A"), (0x00B6, 0x00BA, "A"), (0x00BC, 0x00BF, "A"), (0x00C6, 0x00C6, "A"),
+    (0x00D0, 0x00D0, "A"), (0x00D7, 0x00D8, "A"), (0x00DE, 0x00E1, "A"), (0x00E6, 0x00E6, "A"),
+    (0x00E8, 0x00EA, "A"), (0x00EC, 0x00ED, "A"), (0x00F0, 0x00F0, "A"), (0x00F2, 0x00F3, "A"),
+    (0x00F7, 0x00FA, "A"), (0x00FC, 0x00FC, "A"), (0x00FE, 0x00FE, "A"), (0x0101, 0x0101, "A"),
+    (0x0111, 0x0111, "A"), (0x0113, 0x0113, "A"), (0x011B, 0x011B, "A"), (0x0126, 0x0127, "A"),
+    (0x012B, 0x012B, "A"), (0x0131, 0x0133, "A"), (0x0138, 0x0138, "A"), (0x013F, 0x0142, "A"),
+    (0x0144, 0x0144, "A"), (0x0148, 0x014B, "A"), (0x014D, 0x014D, "A"), (0x0152, 0x0153, "A"),
+    (0x0166, 0x0167, "A"), (0x016B, 0x016B, "A"), (0x01CE, 0x01CE, "A"), (0x01D0, 0x01D0, "A"),
+    (0x01D2, 0x01D2, "A"), (0x01D4, 0x01D4, "A"), (0x01D6, 0x01D6, "A"), (0x01D8, 0x01D8, "A"),
+    (0x01DA, 0x01DA, "A"), (0x01DC, 0x01DC, "A"), (0x0251, 0x0251, "A"), (0x0261, 0x0261, "A"),
+    (0x02C4, 0x02C4, "A"), (0x02C7, 0x02C7, "A"), (0x02C9, 0x02CB, "A"), (0x02CD, 0x02CD, "A"),
+    (0x02D0, 0x02D0, "A"), (0x02D8, 0x02DB, "A"), (0x02DD, 0x02DD, "A"), (0x02DF, 0x02DF, "A"),
+    (0x0300, 0x036F, "A"), (0x0391, 0x03A1, "A"), (0x03A3, 0x03A9, "A"), (0x03B1, 0x03C1, "A"),
+    (0x03C3, 0x03C9, "A"), (0x0401, 0x0401, "A"), (0x0410, 0x044F, "A"), (0x0451, 0x0451, "A"),
+    (0x1100, 0x115F, "W"), (0x2010, 0x2010, "A"), (0x2013, 0x2016, "A"), (0x2018, 0x2019, "A"),
+    (0x201C, 0x201D, "A"), (0x2020, 0x2022, "A"), (0x2024, 0x2027, "A"), (0x2030, 0x2030, "A"),
+    (0x2032, 0x2033, "A"), (0x2035, 0x2035, "A"), (0x203B, 0x203B, "A"), (0x203E, 0x203E, "A"),
+    (0x2074, 0x2074, "A"), (0x207F, 0x207F, "A"), (0x2081, 0x2084, "A"), (0x20A9, 0x20A9, "H"),
+    (0x20AC, 0x20AC, "A"), (0x2103, 0x2103, "A"), (0x2105, 0x2105, "A"), (0x2109, 0x2109, "A"),
+    (0x2113, 0x2113, "A"), (0x2116, 0x2116, "A"), (0x2121, 0x2122, "A"), (0x2126, 0x2126, "A"),
+    (0x212B, 0x212B, "A"), (0x2153, 0x2154, "A"), (0x215B, 0x215E, "A"), (0x2160, 0x216B, "A"),
+    (0x2170, 0x2179, "A"), (0x2189, 0x2189, "A"), (0x2190, 0x2199, "A"), (0x21B8, 0x21B9, "A"),
+    (0x21D2, 0x21D2, "A"), (0x21D4, 0x21D4, "A"), (0x21E7, 0x21E7, "A"), (0x2200, 0x2200, "A"),
+    (0x2202, 0x2203, "A"), (0x2207, 0x2208, "A"), (0x220B, 0x220B, "A"), (0x220F, 0x220F, "A"),
+    (0x2211, 0x2211, "A"), (0x2215, 0x2215, "A"), (0x221A, 0x221A, "A"), (0x221D, 0x2220, "A"),
+    (0x2223, 0x2223, "A"), (0x2225, 0x2225, "A"), (0x2227, 0x222C, "A"), (0x222E, 0x222E, "A"),
+    (0x2234, 0x2237, "A"), (0x223C, 0x223D, "A"), (0x2248, 0x2248, "A"), (0x224C, 0x224C, "A"),
+    (0x2252, 0x2252, "A"), (0x2260, 0x2261, "A"), (0x2264, 0x2267, "A"), (0x226A, 0x226B, "A"),
+    (0x226E, 0x226F, "A"), (0x2282, 0x2283, "A"), (0x2286, 0x2287, "A"), (0x2295, 0x2295, "A"),
+    (0x2299, 0x2299, "A"), (0x22A5, 0x22A5, "A"), (0x22BF, 0x22BF, "A"), (0x2312, 0x2312, "A"),
+    (0x231A, 0x231B, "W"), (0x2329, 0x232A, "W"), (0x23E9, 0x23EC, "W"), (0x23F0, 0x23F0, "W"),
+    (0x23F3, 0x23F3, "W"), (0x2460, 0x24E9, "A"), (0x24EB, 0x254B, "A"), (0x2550, 0x2573, "A"),
+    (0x2580, 0x258F, "A"), (0x2592, 0x2595, "A"), (0x25A0, 0x25A1, "A"), (0x25A3, 0x25A9, "A"),
+    (0x25B2, 0x25B3, "A"), (0x25B6, 0x25B7, "A"), (0x25BC, 0x25BD, "A"), (0x25C0, 0x25C1, "A"),
+    (0x25C6, 0x25C8, "A"), (0x25CB, 0x25CB, "A"), (0x25CE, 0x25D1, "A"), (0x25E2, 0x25E5, "A"),
+    (0x25EF, 0x25EF, "A"), (0x25FD, 0x25FE, "W"), (0x2605, 0x2606, "A"), (0x2609, 0x2609, "A"),
+    (0x260E, 0x260F, "A"), (0x2614, 0x2615, "W"), (0x261C, 0x261C, "A"), (0x261E, 0x261E, "A"),
+    (0x2640, 0x2640, "A"), (0x2642, 0x2642, "A"), (0x2648, 0x2653, "W"), (0x2660, 0x2661, "A"),
+    (0x2663, 0x2665, "A"), (0x2667, 0x266A, "A"), (0x266C, 0x266D, "A"), (0x266F, 0x266F, "A"),
+    (0x267F, 0x267F, "W"), (0x2693, 0x2693, "W"), (0x269E, 0x269F, "A"), (0x26A1, 0x26A1, "W"),
+    (0x26AA, 0x26AB, "W"), (0x26BD, 0x26BE, "W"), (0x26BF, 0x26BF, "A"), (0x26C4, 0x26C5, "W"),
+    (0x26C6, 0x26CD, "A"), (0x26CE, 0x26CE, "W"), (0x26CF, 0x26D3, "A"), (0x26D4, 0x26D4, "W"),
+    (0x26D5, 0x26E1, "A"), (0x26E3, 0x26E3, "A"), (0x26E8, 0x26E9, "A"), (0x26EA, 0x26EA, "W"),
+    (0x26EB, 0x26F1, "A"), (0x26F2, 0x26F3, "W"), (0x26F4, 0x26F4, "A"), (0x26F5, 0x26F5, "W"),
+    (0x26F6, 0x26F9, "A"), (0x26FA, 0x26FA, "W"), (0x26FB, 0x26FC, "A"), (0x26FD, 0x26FD, "W"),
+    (0x26FE, 0x26FF, "A"), (0x2705, 0x2705, "W"), (0x270A, 0x270B, "W"), (0x2728, 0x2728, "W"),
+    (0x273D, 0x273D, "A"), (0x274C, 0x274C, "W"), (0x274E, 0x274E, "W"), (0x2753, 0x2755, "W"),
+    (0x2757, 0x2757, "W"), (0x2776, 0x277F, "A"), (0x2795, 0x2797, "W"), (0x27B0, 0x27B0, "W"),
+    (0x27BF, 0x27BF, "W"), (0x27E6, 0x27ED, "Na"), (0x2985, 0x2986, "Na"),
+    (0x2B1B, 0x2B1C, "W"), (0x2B50, 0x2B50, "W"), (0x2B55, 0x2B55, "W"), (0x2B56, 0x2B59, "A"),
+    (0x2E80, 0x2E99, "W"), (0x2E9B, 0x2EF3, "W"), (0x2F00, 0x2FD5, "W"), (0x2FF0, 0x2FFB, "W"),
+    (0x3000, 0x3000, "F"), (0x3001, 0x303E, "W"), (0x3041, 0x3096, "W"), (0x3099, 0x30FF, "W"),
+    (0x3105, 0x312F, "W"), (0x3131, 0x318E, "W"), (0x3190, 0x31E3, "W"), (0x31F0, 0x321E, "W"),
+    (0x3220, 0x3247, "W"), (0x3248, 0x324F, "A"), (0x3250, 0x4DBF, "W"), (0x4E00, 0xA48C, "W"),
+    (0xA490, 0xA4C6, "W"), (0xA960, 0xA97C, "W"), (0xAC00, 0xD7A3, "W"), (0xE000, 0xF8FF, "A"),
+    (0xF900, 0xFAFF, "W"), (0xFE00, 0xFE0F, "A"), (0xFE10, 0xFE19, "W"), (0xFE30, 0xFE52, "W"),
+    (0xFE54, 0xFE66, "W"), (0xFE68, 0xFE6B, "W"), (0xFF01, 0xFF60, "F"), (0xFF61, 0xFFBE, "H"),
+    (0xFFC2, 0xFFC7, "H"), (0xFFCA, 0xFFCF, "H"), (0xFFD2, 0xFFD7, "H"), (0xFFDA, 0xFFDC, "H"),
+    (0xFFE0, 0xFFE6, "F"), (0xFFE8, 0xFFEE, "H"), (0xFFFD, 0xFFFD, "A"),
+    (0x16FE0, 0x16FE4, "W"), (0x16FF0, 0x16FF1, "W"), (0x17000, 0x187F7, "W"),
+    (0x18800, 0x18CD5, "W"), (0x18D00, 0x18D08, "W"), (0x1AFF0, 0x1AFF3, "W"),
+    (0x1AFF5, 0x1AFFB, "W"), (0x1AFFD, 0x1AFFE, "W"), (0x1B000, 0x1B122, "W"),
+    (0x1B150, 0x1B152, "W"), (0x1B164, 0x1B167, "W"), (0x1B170, 0x1B2FB, "W"),
+    (0x1F004, 0x1F004, "W"), (0x1F0CF, 0x1F0CF, "W"), (0x1F100, 0x1F10A, "A"),
+    (0x1F110, 0x1F12D, "A"), (0x1F130, 0x1F169, "A"), (0x1F170, 0x1F18D, "A"),
+    (0x1F18E, 0x1F18E, "W"), (0x1F18F, 0x1F190, "A"), (0x1F191, 0x1F19A, "W"),
+    (0x1F19B, 0x1F1AC, "A"), (0x1F200, 0x1F202, "W"), (0x1F210, 0x1F23B, "W"),
+    (0x1F240, 0x1F248, "W"), (0x1F250, 0x1F251, "W"), (0x1F260, 0x1F265, "W"),
+    (0x1F300, 0x1F320, "W"), (0x1F32D, 0x1F335, "W"), (0x1F337, 0x1F37C, "W"),
+    (0x1F37E, 0x1F393, "W"), (0x1F3A0, 0x1F3CA, "W"), (0x1F3CF, 0x1F3D3, "W"),
+    (0x1F3E0, 0x1F3F0, "W"), (0x1F3F4, 0x1F3F4, "W"), (0x1F3F8, 0x1F43E, "W"),
+    (0x1F440, 0x1F440, "W"), (0x1F442, 0x1F4FC, "W"), (0x1F4FF, 0x1F53D, "W"),
+    (0x1F54B, 0x1F54E, "W"), (0x1F550, 0x1F567, "W"), (0x1F57A, 0x1F57A, "W"),
+    (0x1F595, 0x1F596, "W"), (0x1F5A4, 0x1F5A4, "W"), (0x1F5FB, 0x1F64F, "W"),
+    (0x1F680, 0x1F6C5, "W"), (0x1F6CC, 0x1F6CC, "W"), (0x1F6D0, 0x1F6D2, "W"),
+    (0x1F6D5, 0x1F6D7, "W"), (0x1F6DD, 0x1F6DF, "W"), (0x1F6EB, 0x1F6EC, "W"),
+    (0x1F6F4, 0x1F6FC, "W"), (0x1F7E0, 0x1F7EB, "W"), (0x1F7F0, 0x1F7F0, "W"),
+    (0x1F90C, 0x1F93A, "W"), (0x1F93C, 0x1F945, "W"), (0x1F947, 0x1F9FF, "W"),
+    (0x1FA70, 0x1FA74, "W"), (0x1FA78, 0x1FA7C, "W"), (0x1FA80, 0x1FA86, "W"),
+    (0x1FA90, 0x1FAAC, "W"), (0x1FAB0, 0x1FABA, "W"), (0x1FAC0, 0x1FAC5, "W"),
+    (0x1FAD0, 0x1FAD9, "W"), (0x1FAE0, 0x1FAE7, "W"), (0x1FAF0, 0x1FAF6, "W"),
+    (0x20000, 0x2FFFD, "W"), (0x30000, 0x3FFFD, "W"), (0xE0100, 0xE01EF, "A"),
+    (0xF0000, 0xFFFFD, "A"), (0x100000, 0x10FFFD, "A"),
+];
+
+// Plane names, indexed by plane number
+const PLANE_NAMES: [&str; 17] = [
+    "Basic Multilingual Plane",
+    "Supplementary Multilingual Plane",
+    "Supplementary Ideographic Plane",
+    "Tertiary Ideographic Plane",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Unassigned",
+    "Supplementary Special-purpose Plane",
+    "Supplementary Private Use Area-A",
+    "Supplementary Private Use Area-B",
+];
+
+// Legend entries, in display order
+const LEGEND: [(&str, &str); 8] = [
+    (COLOR_LETTERS, "Letters"),
+    (COLOR_NUMBERS, "Numbers"),
+    (COLOR_PUNCTUATION, "Punctuation"),
+    (COLOR_SYMBOLS, "Symbols"),
+    (COLOR_MARKS, "Marks"),
+    (COLOR_WHITESPACE, "Whitespace"),
+    (COLOR_CONTROL, "Control / Special"),
+    (COLOR_UNASSIGNED, "Unassigned"),
+];
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    Category = 0,
+    Script = 1,
+    Utf8Length = 2,
+    Age = 3,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Grid = 0,
+    // The whole plane as one row, panned horizontally
+    Strip = 1,
+}
+
+// Cells failing the filter are dimmed rather than hidden
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterKind {
+    None = 0,
+    Letters = 1,
+    Numbers = 2,
+    Emoji = 3,
+    // Has a character name (excludes controls, unassigned and most CJK)
+    Named = 4,
+    Whitespace = 5,
+}
+
+impl FilterKind {
+    fn matches(self, codepoint: u32) -> bool {
+        use unic_ucd_category::GeneralCategory;
+
+        let Some(ch) = char::from_u32(codepoint) else {
+            return self == FilterKind::None;
+        };
+        match self {
+            FilterKind::None => true,
+            FilterKind::Letters => GeneralCategory::of(ch).is_letter(),
+            FilterKind::Numbers => GeneralCategory::of(ch).is_number(),
+            FilterKind::Emoji => unic_emoji_char::is_emoji(ch),
+            FilterKind::Named => unicode_names2::name(ch).is_some(),
+            FilterKind::Whitespace => ch.is_whitespace(),
+        }
+    }
+}
 
 #[wasm_bindgen]
 pub struct UnicodeExplorer {
     canvas: HtmlCanvasElement,
     ctx: CanvasRenderingContext2d,
-    width: f64,
-    height: f64,
-    // View state
-    offset_x: f64,
-    offset_y: f64,
-    zoom: f64,
-    // Plane (0 = BMP, 1-16 for supplementary)
-    plane: u32,
+    // Pan, zoom and grid geometry
+    view: ViewState,
+    device_pixel_ratio: f64,
+    // Set by `fit_plane` so `resize` can keep the overview framed
+    fitted: bool,
+    layout: Layout,
+    // Column count to restore when leaving the strip layout
+    grid_layout_cols: u32,
     // Interaction state
+    pinch: Option<Pinch>,
+    // Keep part of the grid on screen while dragging and zooming
+    pan_bounds: bool,
+    // Clicks on unassigned cells select the nearest assigned character
+    snap_to_content: bool,
+    // Flip the wheel zoom and drag directions
+    invert_zoom: bool,
+    invert_pan: bool,
     dragging: bool,
     last_mouse_x: f64,
     last_mouse_y: f64,
     // Selected character
     selected_codepoint: Option<u32>,
+    on_select: Option<js_sys::Function>,
+    // Past selections for `back` / `forward`; `history_index` is the current one
+    history: Vec<u32>,
+    history_index: usize,
+    // Rubber-band rectangle, separate from the single-cell selection
+    range_selection: Option<RangeSelection>,
+    selecting: bool,
+    // Character under the cursor
+    hovered_codepoint: Option<u32>,
+    // Last pointer position from `set_cursor`, for the crosshair
+    cursor: Option<(f64, f64)>,
+    crosshair_visible: bool,
+    // Codepoints matching the current search
+    search_highlights: HashSet<u32>,
+    // Saved codepoints, in the order they were added
+    bookmarks: Vec<u32>,
+    // Ordered results toured by `next_result` / `prev_result`
+    results: Vec<u32>,
+    result_cursor: Option<usize>,
+    wrap_results: bool,
+    // Running `animate_center_on`, if any
+    animation: Option<CenterAnimation>,
+    // Set by anything that changes what `render` would draw
+    dirty: bool,
+    // Clock for `last_render_ms`; None when there is no window
+    performance: Option<web_sys::Performance>,
+    last_render_ms: f64,
+    // Overlays
+    legend_visible: bool,
+    header_visible: bool,
+    axes_visible: bool,
+    // Block name of the cell at the view center
+    context_visible: bool,
+    // Pair shown by `render_compare`
+    compare: Option<ComparePair>,
+    // Inclusive codepoint range of the block tinted by `highlight_block`
+    highlighted_block: Option<(u32, u32)>,
+    show_names: bool,
+    // Hex codepoint labels in place of glyphs
+    show_hex: bool,
+    cell_padding: f64,
+    // Filters
+    assigned_only: bool,
+    filter: FilterKind,
+    color_mode: ColorMode,
+    background_color: String,
+    // Outline drawn around the selected cell
+    selection_color: String,
+    selection_width: f64,
+    // Base character combining marks are drawn on
+    mark_base: char,
+    // CSS font-family list for glyphs
+    font_family: String,
+    // Stripe cells whose glyph looks missing from the font
+    flag_missing_glyphs: bool,
+    // Outline noncharacters and deprecated characters
+    flag_deprecated: bool,
+    // Draw symbols for whitespace, control and format characters
+    reveal_invisibles: bool,
+    // Underline double-width (East Asian Width W/F) cells
+    mark_wide: bool,
+    // User overrides for category colors
+    color_overrides: HashMap<unic_ucd_category::GeneralCategory, String>,
+    // Bumped whenever a color setting changes, so cached color maps go stale
+    palette_version: u32,
+    // Whole-plane color map blitted instead of per-cell fills when zoomed out
+    color_cache: Option<PlaneColorCache>,
 }
 
 #[wasm_bindgen]
@@ -37,59 +388,316 @@ impl UnicodeExplorer {
         let width = canvas.width() as f64;
         let height = canvas.height() as f64;
 
-        Ok(UnicodeExplorer {
+        let mut explorer = UnicodeExplorer {
             canvas,
             ctx,
-            width,
-            height,
-            offset_x: 0.0,
-            offset_y: 0.0,
-            zoom: 1.0,
-            plane: 0,
+            view: ViewState::new(width, height),
+            device_pixel_ratio: 1.0,
+            fitted: false,
+            layout: Layout::Grid,
+            grid_layout_cols: GRID_SIZE,
+            pinch: None,
+            pan_bounds: false,
+            snap_to_content: false,
+            invert_zoom: false,
+            invert_pan: false,
             dragging: false,
             last_mouse_x: 0.0,
             last_mouse_y: 0.0,
             selected_codepoint: None,
-        })
+            on_select: None,
+            history: Vec::new(),
+            history_index: 0,
+            range_selection: None,
+            selecting: false,
+            hovered_codepoint: None,
+            cursor: None,
+            crosshair_visible: false,
+            search_highlights: HashSet::new(),
+            bookmarks: Vec::new(),
+            results: Vec::new(),
+            result_cursor: None,
+            wrap_results: true,
+            animation: None,
+            dirty: true,
+            performance: web_sys::window().and_then(|w| w.performance()),
+            last_render_ms: 0.0,
+            legend_visible: true,
+            header_visible: true,
+            axes_visible: false,
+            context_visible: false,
+            compare: None,
+            highlighted_block: None,
+            show_names: false,
+            show_hex: false,
+            cell_padding: DEFAULT_CELL_PADDING,
+            assigned_only: false,
+            filter: FilterKind::None,
+            color_mode: ColorMode::Category,
+            background_color: DEFAULT_BACKGROUND.to_string(),
+            selection_color: DEFAULT_SELECTION_COLOR.to_string(),
+            selection_width: DEFAULT_SELECTION_WIDTH,
+            mark_base: DEFAULT_MARK_BASE,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            flag_missing_glyphs: false,
+            flag_deprecated: false,
+            reveal_invisibles: false,
+            mark_wide: false,
+            color_overrides: HashMap::new(),
+            palette_version: 0,
+            color_cache: None,
+        };
+        // The header is on by default, so start with row 0 just below it
+        let (left, top) = explorer.grid_inset();
+        explorer.view.offset_x = left;
+        explorer.view.offset_y = top;
+        Ok(explorer)
     }
 
+    // Width and height are in CSS pixels; the backing store is scaled by the
+    // device pixel ratio while all view math stays in CSS pixels
     pub fn resize(&mut self, width: f64, height: f64) {
-        self.width = width;
-        self.height = height;
-        self.canvas.set_width(width as u32);
-        self.canvas.set_height(height as u32);
+        self.dirty = true;
+        let (backing_width, backing_height) = self.view.resize(width, height, self.device_pixel_ratio);
+        self.canvas.set_width(backing_width);
+        self.canvas.set_height(backing_height);
+        if self.fitted {
+            self.fit_plane();
+        }
+    }
+
+    // Typically `window.devicePixelRatio`; non-positive values are ignored
+    pub fn set_device_pixel_ratio(&mut self, ratio: f64) {
+        if ratio.is_finite() && ratio > 0.0 && ratio != self.device_pixel_ratio {
+            self.device_pixel_ratio = ratio;
+            self.resize(self.view.width, self.view.height);
+        }
+    }
+
+    pub fn get_device_pixel_ratio(&self) -> f64 {
+        self.device_pixel_ratio
+    }
+
+    // Zoom and center so the whole plane fits on the canvas
+    pub fn fit_plane(&mut self) {
+        self.dirty = true;
+        self.animation = None;
+        let (left, top) = self.grid_inset();
+        self.view.fit(left, top);
+        self.fitted = true;
+    }
+
+    // Strip lays the plane out as a single row; hit-testing, centering and
+    // selection all follow since they share the same grid geometry
+    pub fn set_layout(&mut self, layout: Layout) {
+        if layout == self.layout {
+            return;
+        }
+        self.layout = layout;
+        self.view.grid_cols = match layout {
+            Layout::Grid => self.grid_layout_cols,
+            Layout::Strip => 0x10000,
+        };
+        self.range_selection = None;
+        self.dirty = true;
+
+        // Keep the selection (or the first cell) in view across the switch
+        let anchor = self
+            .selected_codepoint
+            .filter(|cp| cp / 0x10000 == self.view.plane)
+            .unwrap_or(self.view.plane * 0x10000);
+        self.center_on(anchor);
+        self.pin_strip();
+    }
+
+    pub fn get_layout(&self) -> Layout {
+        self.layout
+    }
+
+    // Change the column count; rows follow so a plane is still exactly 0x10000
+    // cells. Returns false (and changes nothing) unless `cols` divides 0x10000.
+    pub fn set_grid_width(&mut self, cols: u32) -> bool {
+        if cols == 0 || 0x10000 % cols != 0 {
+            return false;
+        }
+
+        // The strip keeps its single row; the width applies once back in grid layout
+        self.grid_layout_cols = cols;
+        if self.layout == Layout::Strip {
+            return true;
+        }
+        self.view.grid_cols = cols;
+        self.range_selection = None;
+        self.dirty = true;
+        if self.fitted {
+            self.fit_plane();
+        }
+        true
+    }
+
+    pub fn get_grid_width(&self) -> u32 {
+        self.view.grid_cols
+    }
+
+    pub fn get_grid_height(&self) -> u32 {
+        self.view.grid_rows()
     }
 
     pub fn set_plane(&mut self, plane: u32) {
         if plane <= 16 {
-            self.plane = plane;
+            self.view.plane = plane;
+            self.dirty = true;
         }
     }
 
     pub fn get_plane(&self) -> u32 {
-        self.plane
+        self.view.plane
+    }
+
+    // Switch to the codepoint's plane and select it, leaving the pan offsets
+    // alone. Returns false for codepoints above U+10FFFF.
+    pub fn go_to_plane_of(&mut self, codepoint: u32) -> bool {
+        if codepoint > MAX_CODEPOINT {
+            return false;
+        }
+        self.set_plane(plane_of(codepoint));
+        self.set_selection(Some(codepoint));
+        true
+    }
+
+    // Step through previously selected codepoints, centering each one.
+    // None at either end of the history.
+    pub fn back(&mut self) -> Option<u32> {
+        if self.history_index == 0 || self.history.is_empty() {
+            return None;
+        }
+        Some(self.go_to_history(self.history_index - 1))
+    }
+
+    pub fn forward(&mut self) -> Option<u32> {
+        if self.history_index + 1 >= self.history.len() {
+            return None;
+        }
+        Some(self.go_to_history(self.history_index + 1))
+    }
+
+    // Step to the adjacent plane and fit it. At plane 16 (or 0 for
+    // `prev_plane`) this does nothing unless `wrap` is set.
+    pub fn next_plane(&mut self, wrap: bool) -> u32 {
+        match self.view.plane {
+            16 if wrap => self.go_to_plane(0),
+            16 => {}
+            plane => self.go_to_plane(plane + 1),
+        }
+        self.view.plane
+    }
+
+    pub fn prev_plane(&mut self, wrap: bool) -> u32 {
+        match self.view.plane {
+            0 if wrap => self.go_to_plane(16),
+            0 => {}
+            plane => self.go_to_plane(plane - 1),
+        }
+        self.view.plane
     }
 
     pub fn set_zoom(&mut self, zoom: f64) {
-        self.zoom = zoom.clamp(0.1, 10.0);
+        self.dirty = true;
+        self.fitted = false;
+        self.view.zoom = zoom.clamp(0.1, 10.0);
     }
 
     pub fn get_zoom(&self) -> f64 {
-        self.zoom
+        self.view.zoom
     }
 
     pub fn zoom_at(&mut self, x: f64, y: f64, delta: f64) {
-        let old_zoom = self.zoom;
-        let zoom_factor = if delta > 0.0 { 0.9 } else { 1.1 };
-        self.zoom = (self.zoom * zoom_factor).clamp(0.1, 10.0);
+        // In and out steps are exact inverses so scrolling back returns home
+        let zoom_out = (delta > 0.0) != self.invert_zoom;
+        let zoom = if zoom_out { self.view.zoom / WHEEL_ZOOM_STEP } else { self.view.zoom * WHEEL_ZOOM_STEP };
+        self.zoom_around(x, y, zoom);
+    }
+
+    // Make positive wheel deltas zoom in instead of out. The anchor point
+    // still stays under the cursor either way.
+    pub fn set_invert_zoom(&mut self, invert: bool) {
+        self.invert_zoom = invert;
+    }
+
+    // Move the grid against the drag ("scrollbar" style) instead of with it
+    pub fn set_invert_pan(&mut self, invert: bool) {
+        self.invert_pan = invert;
+    }
+
+    // Fixed-factor steps for +/- buttons, anchored on the canvas midpoint
+    pub fn zoom_in_step(&mut self) {
+        self.zoom_around(self.view.width / 2.0, self.view.height / 2.0, self.view.zoom * BUTTON_ZOOM_STEP);
+    }
+
+    pub fn zoom_out_step(&mut self) {
+        self.zoom_around(self.view.width / 2.0, self.view.height / 2.0, self.view.zoom / BUTTON_ZOOM_STEP);
+    }
+
+    // "Home": zoom 1.0 with the plane's first cell in the top-left corner
+    // (just below the header and axis bands when they're shown). Stops any animation;
+    // stays on the current plane unless `to_first_plane` is set.
+    pub fn reset_view(&mut self, to_first_plane: bool) {
+        self.animation = None;
+        self.fitted = false;
+        self.dirty = true;
+        if to_first_plane {
+            self.view.plane = 0;
+        }
+        self.view.zoom = 1.0;
+        let (left, top) = self.grid_inset();
+        self.view.offset_x = left;
+        self.view.offset_y = top;
+    }
+
+    // Two-finger zoom: the zoom follows the ratio of finger distances and the
+    // grid point between the fingers stays under their midpoint as it moves
+    pub fn pinch_start(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) {
+        let distance = (x1 - x0).hypot(y1 - y0);
+        if distance <= 0.0 {
+            return;
+        }
+        self.animation = None;
+        self.dragging = false;
+        self.pinch = Some(Pinch {
+            start_distance: distance,
+            start_zoom: self.view.zoom,
+            mid_x: (x0 + x1) / 2.0,
+            mid_y: (y0 + y1) / 2.0,
+        });
+    }
+
+    pub fn pinch_move(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) {
+        let Some(pinch) = self.pinch.as_mut() else {
+            return;
+        };
+        let distance = (x1 - x0).hypot(y1 - y0);
+        let (mid_x, mid_y) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+
+        // Follow the midpoint first, then zoom around where it is now
+        self.view.offset_x += mid_x - pinch.mid_x;
+        self.view.offset_y += mid_y - pinch.mid_y;
+        pinch.mid_x = mid_x;
+        pinch.mid_y = mid_y;
+        let zoom = pinch.start_zoom * distance / pinch.start_distance;
+        if zoom > 0.0 {
+            self.zoom_around(mid_x, mid_y, zoom);
+        }
+        self.fitted = false;
+        self.dirty = true;
+    }
 
-        // Adjust offset to zoom towards mouse position
-        let scale_change = self.zoom / old_zoom;
-        self.offset_x = x - (x - self.offset_x) * scale_change;
-        self.offset_y = y - (y - self.offset_y) * scale_change;
+    pub fn pinch_end(&mut self) {
+        self.pinch = None;
     }
 
     pub fn start_drag(&mut self, x: f64, y: f64) {
+        // Grabbing the grid interrupts any centering animation
+        self.animation = None;
         self.dragging = true;
         self.last_mouse_x = x;
         self.last_mouse_y = y;
@@ -97,234 +705,4408 @@ impl UnicodeExplorer {
 
     pub fn drag(&mut self, x: f64, y: f64) {
         if self.dragging {
-            self.offset_x += x - self.last_mouse_x;
-            self.offset_y += y - self.last_mouse_y;
+            self.fitted = false;
+            self.dirty = true;
+            let sign = if self.invert_pan { -1.0 } else { 1.0 };
+            self.view.offset_x += sign * (x - self.last_mouse_x);
+            self.view.offset_y += sign * (y - self.last_mouse_y);
             self.last_mouse_x = x;
             self.last_mouse_y = y;
+            self.pin_strip();
+            self.clamp_pan();
         }
     }
 
+    // When enabled, `drag` and zooming stop with at least a sliver of the grid
+    // (64px, or the whole grid if smaller) still inside the canvas
+    pub fn set_pan_bounds(&mut self, enabled: bool) {
+        self.pan_bounds = enabled;
+        self.clamp_pan();
+    }
+
+    pub fn has_pan_bounds(&self) -> bool {
+        self.pan_bounds
+    }
+
     pub fn end_drag(&mut self) {
         self.dragging = false;
     }
 
+    // Codepoint under a canvas pixel, or None anywhere outside the grid
+    pub fn codepoint_at(&self, x: f64, y: f64) -> Option<u32> {
+        self.view.codepoint_at(x, y)
+    }
+
+    // [row, col] of a codepoint in the displayed plane, or None if it lives elsewhere
+    pub fn grid_position(&self, codepoint: u32) -> Option<Vec<u32>> {
+        self.view.grid_position(codepoint).map(|(row, col)| vec![row, col])
+    }
+
     pub fn click(&mut self, x: f64, y: f64) -> Option<u32> {
-        let cell_size = CELL_SIZE * self.zoom;
-        let grid_x = ((x - self.offset_x) / cell_size).floor() as i32;
-        let grid_y = ((y - self.offset_y) / cell_size).floor() as i32;
+        let clicked = self.codepoint_at(x, y);
+        let target = match clicked {
+            Some(cp) if self.snap_to_content && !is_assigned(cp) => {
+                let snapped = nearest_assigned(cp).filter(|near| near / 0x10000 == self.view.plane);
+                if let Some(near) = snapped {
+                    let (row, col) = self.view.cell_of(near);
+                    self.scroll_into_view(col, row);
+                }
+                snapped.or(clicked)
+            }
+            _ => clicked,
+        };
+        self.set_selection(target);
+        self.selected_codepoint
+    }
 
-        if grid_x >= 0 && grid_x < GRID_SIZE as i32 && grid_y >= 0 && grid_y < GRID_SIZE as i32 {
-            let codepoint = (self.plane * 0x10000) + (grid_y as u32 * GRID_SIZE) + grid_x as u32;
-            self.selected_codepoint = Some(codepoint);
-            Some(codepoint)
-        } else {
-            self.selected_codepoint = None;
-            None
+    // Snap clicks on unassigned cells to the nearest assigned character in
+    // the same plane (see `nearest_assigned`); clicks stay put if none is near
+    pub fn set_snap_to_content(&mut self, snap: bool) {
+        self.snap_to_content = snap;
+    }
+
+    // Track the cell under the cursor without changing the selection
+    pub fn hover(&mut self, x: f64, y: f64) -> Option<u32> {
+        let hovered = self.codepoint_at(x, y);
+        if hovered != self.hovered_codepoint {
+            self.hovered_codepoint = hovered;
+            self.dirty = true;
         }
+        hovered
     }
 
-    pub fn get_selected(&self) -> Option<u32> {
-        self.selected_codepoint
+    pub fn clear_hover(&mut self) {
+        if self.hovered_codepoint.take().is_some() {
+            self.dirty = true;
+        }
     }
 
-    pub fn render(&self) {
-        let ctx = &self.ctx;
-        let cell_size = CELL_SIZE * self.zoom;
+    pub fn get_hovered(&self) -> Option<u32> {
+        self.hovered_codepoint
+    }
 
-        // Clear canvas
-        ctx.set_fill_style_str("#1a1a2e");
-        ctx.fill_rect(0.0, 0.0, self.width, self.height);
+    // Pointer position in CSS pixels, drawn as a crosshair when enabled
+    pub fn set_cursor(&mut self, x: f64, y: f64) {
+        self.cursor = Some((x, y));
+        if self.crosshair_visible {
+            self.dirty = true;
+        }
+    }
 
-        // Calculate visible range
-        let start_col = ((-self.offset_x) / cell_size).floor().max(0.0) as u32;
-        let start_row = ((-self.offset_y) / cell_size).floor().max(0.0) as u32;
-        let end_col = ((self.width - self.offset_x) / cell_size).ceil().min(GRID_SIZE as f64) as u32;
-        let end_row = ((self.height - self.offset_y) / cell_size).ceil().min(GRID_SIZE as f64) as u32;
+    pub fn clear_cursor(&mut self) {
+        if self.cursor.take().is_some() && self.crosshair_visible {
+            self.dirty = true;
+        }
+    }
 
-        // Set font based on zoom
-        let font_size = (cell_size * 0.6).max(8.0).min(32.0);
-        ctx.set_font(&format!("{}px sans-serif", font_size));
-        ctx.set_text_align("center");
-        ctx.set_text_baseline("middle");
+    // Lines through the cursor plus a U+XXXX readout of the cell under it
+    pub fn set_show_crosshair(&mut self, show: bool) {
+        self.dirty = true;
+        self.crosshair_visible = show;
+    }
 
-        for row in start_row..end_row {
-            for col in start_col..end_col {
-                let codepoint = (self.plane * 0x10000) + (row * GRID_SIZE) + col;
-                let x = self.offset_x + (col as f64 * cell_size);
-                let y = self.offset_y + (row as f64 * cell_size);
+    pub fn is_crosshair_visible(&self) -> bool {
+        self.crosshair_visible
+    }
 
-                // Get category color
-                let color = get_category_color(codepoint);
-                ctx.set_fill_style_str(color);
-                ctx.fill_rect(x + 1.0, y + 1.0, cell_size - 2.0, cell_size - 2.0);
-
-                // Draw character if zoom is sufficient
-                if self.zoom >= 0.5 {
-                    if let Some(ch) = char::from_u32(codepoint) {
-                        ctx.set_fill_style_str("#ffffff");
-                        let _ = ctx.fill_text(
-                            &ch.to_string(),
-                            x + cell_size / 2.0,
-                            y + cell_size / 2.0,
-                        );
-                    }
-                }
+    pub fn get_selected(&self) -> Option<u32> {
+        self.selected_codepoint
+    }
 
-                // Highlight selected
-                if self.selected_codepoint == Some(codepoint) {
-                    ctx.set_stroke_style_str("#ffcc00");
-                    ctx.set_line_width(3.0);
-                    ctx.stroke_rect(x + 1.0, y + 1.0, cell_size - 2.0, cell_size - 2.0);
-                }
-            }
+    // Called with the new codepoint (or null) whenever the selection changes.
+    // Runs as a microtask after the triggering call returns, so the callback
+    // may call back into the explorer.
+    pub fn set_on_select(&mut self, callback: js_sys::Function) {
+        self.on_select = Some(callback);
+    }
+
+    pub fn clear_on_select(&mut self) {
+        self.on_select = None;
+    }
+
+    // Pan one screen: 0 up, 1 down, 2 left, 3 right (PageUp/PageDown style).
+    // A page is the visible cell count less one, so a row or column of context
+    // carries over. Stops at the plane edges (below/right of the header and
+    // axis bands when shown); returns false if the view didn't move.
+    pub fn page(&mut self, direction: i32) -> bool {
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let (start_col, start_row, end_col, end_row) = self.view.visible_bounds();
+        let page_cols = end_col.saturating_sub(start_col).saturating_sub(1).max(1) as f64 * cell_size;
+        let page_rows = end_row.saturating_sub(start_row).saturating_sub(1).max(1) as f64 * cell_size;
+        let (left, top) = self.grid_inset();
+        let grid_width = self.view.grid_cols as f64 * cell_size;
+        let grid_height = self.view.grid_rows() as f64 * cell_size;
+
+        // Never jump backwards when the plane is already past the limit
+        let (offset_x, offset_y) = (self.view.offset_x, self.view.offset_y);
+        let (new_x, new_y) = match direction {
+            0 => (offset_x, (offset_y + page_rows).min(top).max(offset_y)),
+            1 => (offset_x, (offset_y - page_rows).max(self.view.height - grid_height).min(offset_y)),
+            2 => ((offset_x + page_cols).min(left).max(offset_x), offset_y),
+            3 => ((offset_x - page_cols).max(self.view.width - grid_width).min(offset_x), offset_y),
+            _ => return false,
+        };
+        if new_x == offset_x && new_y == offset_y {
+            return false;
         }
 
-        // Draw grid lines if zoomed in enough
-        if self.zoom >= 1.0 {
-            ctx.set_stroke_style_str("#333355");
-            ctx.set_line_width(0.5);
-            
-            for col in start_col..=end_col {
-                let x = self.offset_x + (col as f64 * cell_size);
-                ctx.begin_path();
-                ctx.move_to(x, 0.0);
-                ctx.line_to(x, self.height);
-                ctx.stroke();
+        self.animation = None;
+        self.fitted = false;
+        self.dirty = true;
+        self.view.offset_x = new_x;
+        self.view.offset_y = new_y;
+        true
+    }
+
+    // Shift the selection by a grid delta, scrolling it into view if needed.
+    // Selects the top-left visible cell when nothing in this plane is selected.
+    pub fn move_selection(&mut self, dx: i32, dy: i32) -> Option<u32> {
+        let max_col = self.view.grid_cols - 1;
+        let max_row = self.view.grid_rows() - 1;
+        let (col, row) = match self.selected_codepoint {
+            Some(cp) if cp / 0x10000 == self.view.plane => {
+                let (row, col) = self.view.cell_of(cp);
+                (
+                    (col as i64 + dx as i64).clamp(0, max_col as i64) as u32,
+                    (row as i64 + dy as i64).clamp(0, max_row as i64) as u32,
+                )
             }
-            
-            for row in start_row..=end_row {
-                let y = self.offset_y + (row as f64 * cell_size);
-                ctx.begin_path();
-                ctx.move_to(0.0, y);
-                ctx.line_to(self.width, y);
-                ctx.stroke();
+            _ => {
+                let (start_col, start_row, _, _) = self.view.visible_bounds();
+                (start_col.min(max_col), start_row.min(max_row))
             }
-        }
+        };
+
+        self.scroll_into_view(col, row);
+        let codepoint = self.view.cell_codepoint(row, col);
+        self.set_selection(Some(codepoint));
+        Some(codepoint)
     }
 
-    pub fn center_on(&mut self, codepoint: u32) {
-        let plane = codepoint / 0x10000;
-        let local = codepoint % 0x10000;
-        let row = local / GRID_SIZE;
-        let col = local % GRID_SIZE;
+    // Skip drawing unassigned cells (and surrogates) so only real content shows
+    pub fn set_assigned_only(&mut self, assigned_only: bool) {
+        self.dirty = true;
+        self.assigned_only = assigned_only;
+    }
 
-        self.plane = plane;
-        let cell_size = CELL_SIZE * self.zoom;
-        self.offset_x = self.width / 2.0 - (col as f64 + 0.5) * cell_size;
-        self.offset_y = self.height / 2.0 - (row as f64 + 0.5) * cell_size;
+    pub fn is_assigned_only(&self) -> bool {
+        self.assigned_only
     }
-}
 
-fn get_category_color(codepoint: u32) -> &'static str {
-    use unic_ucd_category::GeneralCategory;
-    
-    if let Some(ch) = char::from_u32(codepoint) {
-        let cat = GeneralCategory::of(ch);
-        match cat {
-            GeneralCategory::UppercaseLetter |
-            GeneralCategory::LowercaseLetter |
-            GeneralCategory::TitlecaseLetter |
-            GeneralCategory::ModifierLetter |
-            GeneralCategory::OtherLetter => "#2d5a27", // Green - letters
-            
-            GeneralCategory::DecimalNumber |
-            GeneralCategory::LetterNumber |
-            GeneralCategory::OtherNumber => "#1a4d7a", // Blue - numbers
-            
-            GeneralCategory::SpaceSeparator |
-            GeneralCategory::LineSeparator |
-            GeneralCategory::ParagraphSeparator => "#4a4a4a", // Gray - whitespace
-            
-            GeneralCategory::Control |
-            GeneralCategory::Format |
-            GeneralCategory::Surrogate |
-            GeneralCategory::PrivateUse => "#5a1a1a", // Dark red - control/special
-            
-            GeneralCategory::ConnectorPunctuation |
-            GeneralCategory::DashPunctuation |
-            GeneralCategory::OpenPunctuation |
-            GeneralCategory::ClosePunctuation |
-            GeneralCategory::InitialPunctuation |
-            GeneralCategory::FinalPunctuation |
-            GeneralCategory::OtherPunctuation => "#6b4c1a", // Brown - punctuation
-            
-            GeneralCategory::MathSymbol |
-            GeneralCategory::CurrencySymbol |
-            GeneralCategory::ModifierSymbol |
-            GeneralCategory::OtherSymbol => "#5a1a5a", // Purple - symbols
-            
-            GeneralCategory::NonspacingMark |
-            GeneralCategory::SpacingMark |
-            GeneralCategory::EnclosingMark => "#1a5a5a", // Teal - marks
-            
-            GeneralCategory::Unassigned => "#2a2a2a", // Dark - unassigned
+    // Dim cells that fail `filter` while leaving them in place for context.
+    // Search markers and the selection stay at full strength on top.
+    pub fn set_filter(&mut self, filter: FilterKind) {
+        self.dirty = true;
+        self.filter = filter;
+    }
+
+    pub fn get_filter(&self) -> FilterKind {
+        self.filter
+    }
+
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.dirty = true;
+        self.color_mode = mode;
+    }
+
+    pub fn get_color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    // Canvas clear color; invalid hex strings are ignored and return false
+    pub fn set_background_color(&mut self, hex: &str) -> bool {
+        if is_valid_hex_color(hex) {
+            self.background_color = hex.to_string();
+            self.palette_version += 1;
+            self.dirty = true;
+            true
+        } else {
+            false
         }
-    } else {
-        "#2a2a2a" // Invalid codepoint
     }
-}
 
-// Helper function to get character info
-#[wasm_bindgen]
-pub fn get_char_info(codepoint: u32) -> String {
-    use unic_ucd_category::GeneralCategory;
-    use unic_ucd_block::Block;
-    
-    let hex = format!("U+{:04X}", codepoint);
-    
-    if let Some(ch) = char::from_u32(codepoint) {
-        let name = unicode_names2::name(ch)
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "<unnamed>".to_string());
-        
-        let category = GeneralCategory::of(ch);
-        let block = Block::of(ch)
-            .map(|b| b.name.to_string())
-            .unwrap_or_else(|| "Unknown".to_string());
-        
-        format!(
-            "{{\"codepoint\":\"{}\",\"char\":\"{}\",\"name\":\"{}\",\"category\":\"{:?}\",\"block\":\"{}\"}}",
-            hex,
-            ch.escape_default(),
-            name,
-            category,
-            block
-        )
-    } else {
-        format!("{{\"codepoint\":\"{}\",\"char\":null,\"name\":\"Invalid\",\"category\":\"Invalid\",\"block\":\"Invalid\"}}", hex)
+    pub fn get_background_color(&self) -> String {
+        self.background_color.clone()
     }
-}
 
-// Search function
-#[wasm_bindgen]
-pub fn search_characters(query: &str, limit: u32) -> Vec<u32> {
-    let query_lower = query.to_lowercase();
-    let mut results = Vec::new();
-    
-    // Check if it's a hex codepoint search
-    if query_lower.starts_with("u+") || query_lower.starts_with("0x") {
-        let hex_str = query_lower.trim_start_matches("u+").trim_start_matches("0x");
-        if let Ok(cp) = u32::from_str_radix(hex_str, 16) {
-            if char::from_u32(cp).is_some() {
-                return vec![cp];
-            }
+    // Underline cells that take two columns in a terminal (East Asian Width
+    // W or F), so double-width runs stand out while browsing
+    pub fn set_mark_wide(&mut self, mark: bool) {
+        self.dirty = true;
+        self.mark_wide = mark;
+    }
+
+    // Color and line width (CSS pixels, clamped to 0.5-12) of the selected
+    // cell's outline. Returns false and changes nothing for an invalid hex.
+    pub fn set_selection_style(&mut self, hex: &str, width: f64) -> bool {
+        if !is_valid_hex_color(hex) || width.is_nan() {
+            return false;
         }
+        self.selection_color = hex.to_string();
+        self.selection_width = width.clamp(0.5, MAX_SELECTION_WIDTH);
+        self.dirty = true;
+        true
     }
-    
-    // Search by name
-    for cp in 0..=0x10FFFF_u32 {
-        if results.len() >= limit as usize {
-            break;
+
+    // Draw combining marks on this base (e.g. 'a') instead of bare
+    pub fn set_mark_base(&mut self, base: char) {
+        self.dirty = true;
+        self.mark_base = base;
+    }
+
+    pub fn reset_mark_base(&mut self) {
+        self.dirty = true;
+        self.mark_base = DEFAULT_MARK_BASE;
+    }
+
+    pub fn get_mark_base(&self) -> char {
+        self.mark_base
+    }
+
+    // Font for glyphs, as a CSS font-family list such as "'Noto Sans', sans-serif".
+    // The string is passed through to the canvas as is; empty restores the default.
+    pub fn set_font_family(&mut self, family: &str) {
+        self.dirty = true;
+        self.font_family = if family.trim().is_empty() {
+            DEFAULT_FONT_FAMILY.to_string()
+        } else {
+            family.to_string()
+        };
+        // The pixel comparison depends on the font
+        if let Some(pair) = &self.compare {
+            let (a, b) = (pair.a, pair.b);
+            self.set_compare(a, b);
         }
-        
-        if let Some(ch) = char::from_u32(cp) {
-            if let Some(name) = unicode_names2::name(ch) {
-                if name.to_string().to_lowercase().contains(&query_lower) {
-                    results.push(cp);
-                }
+    }
+
+    pub fn get_font_family(&self) -> String {
+        self.font_family.clone()
+    }
+
+    // Show otherwise blank cells as symbols: ␣ for spaces, ¶ and ⏎ for
+    // paragraph and line separators, Control Pictures (␀, ␉, ␡) for C0
+    // controls and a dotted box for other controls and format characters
+    pub fn set_reveal_invisibles(&mut self, reveal: bool) {
+        self.dirty = true;
+        self.reveal_invisibles = reveal;
+    }
+
+    // Stripe assigned cells whose glyph seems absent from the font ("tofu").
+    // This is a measure_text heuristic: a glyph counts as missing when it has
+    // no advance or exactly the advance of the font's .notdef box, so some
+    // real glyphs that happen to share that width get flagged too.
+    pub fn set_flag_missing_glyphs(&mut self, flag: bool) {
+        self.dirty = true;
+        self.flag_missing_glyphs = flag;
+    }
+
+    // Warn about codepoints that don't belong in interchanged text: cells of
+    // noncharacters and deprecated characters get a dashed red outline
+    pub fn set_flag_deprecated(&mut self, flag: bool) {
+        self.dirty = true;
+        self.flag_deprecated = flag;
+    }
+
+    // Override the color of a general category, named by its abbreviation ("Lu"),
+    // long name ("Uppercase_Letter") or the form `get_char_info` reports ("UppercaseLetter").
+    // Returns false and leaves the palette untouched if either argument is invalid.
+    pub fn set_category_color(&mut self, category: &str, hex: &str) -> bool {
+        match parse_category(category) {
+            Some(cat) if is_valid_hex_color(hex) => {
+                self.color_overrides.insert(cat, hex.to_string());
+                self.palette_version += 1;
+                self.dirty = true;
+                true
             }
+            _ => false,
         }
     }
-    
-    results
+
+    pub fn reset_colors(&mut self) {
+        self.dirty = true;
+        self.palette_version += 1;
+        self.color_overrides.clear();
+    }
+
+    // Mark search matches on the grid; an empty slice clears them
+    pub fn set_search_highlights(&mut self, codepoints: &[u32]) {
+        self.dirty = true;
+        self.search_highlights = codepoints.iter().copied().collect();
+    }
+
+    // Name private use characters, e.g. the glyphs of an icon font, so cell
+    // labels and `get_char_info` show them instead of "<unnamed>". Takes a
+    // JSON object keyed by "U+XXXX" or "0xXXXX":
+    // {"U+E001": "ICON HOME", "U+F0000": "LOGO"}. Replaces any earlier labels
+    // ("{}" clears them). Keys must fall in a private use area (U+E000-F8FF,
+    // U+F0000-FFFFD, U+100000-10FFFD) and values must be non-empty strings;
+    // otherwise nothing changes and an error is returned. Labels are shared by
+    // every explorer on the page.
+    pub fn set_pua_labels(&mut self, json: &str) -> Result<(), JsValue> {
+        store_pua_labels(json)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    // Label cells with their character names once zoom reaches 3.0
+    pub fn set_show_names(&mut self, show: bool) {
+        self.dirty = true;
+        self.show_names = show;
+    }
+
+    // Draw each cell's low 16 bits in hex instead of its glyph once zoom
+    // reaches 1.0, e.g. for reading off positions in unassigned regions.
+    // Below that, glyphs keep drawing as usual.
+    pub fn set_show_hex(&mut self, show: bool) {
+        self.dirty = true;
+        self.show_hex = show;
+    }
+
+    // Gap around each cell's fill; 0 tiles cells into a continuous heatmap.
+    // Negative or non-finite values are ignored, and the effective padding
+    // never exceeds half the cell at the current zoom.
+    pub fn set_cell_padding(&mut self, px: f64) {
+        if px.is_finite() {
+            self.cell_padding = px.max(0.0);
+            self.dirty = true;
+        }
+    }
+
+    pub fn get_cell_padding(&self) -> f64 {
+        self.cell_padding
+    }
+
+    // Begin a rectangular selection at a canvas pixel (clamped onto the grid)
+    pub fn start_select(&mut self, x: f64, y: f64) {
+        let cell = self.view.clamped_cell_at(x, y);
+        self.range_selection = Some(RangeSelection {
+            plane: self.view.plane,
+            anchor: cell,
+            cursor: cell,
+        });
+        self.selecting = true;
+        self.dirty = true;
+    }
+
+    pub fn update_select(&mut self, x: f64, y: f64) {
+        if !self.selecting {
+            return;
+        }
+        let cell = self.view.clamped_cell_at(x, y);
+        if let Some(range) = self.range_selection.as_mut() {
+            if range.cursor != cell {
+                range.cursor = cell;
+                self.dirty = true;
+            }
+        }
+    }
+
+    // Finish the rectangle; it stays visible until `clear_range_selection`
+    pub fn end_select(&mut self) -> Vec<u32> {
+        self.selecting = false;
+        self.range_codepoints()
+    }
+
+    pub fn clear_range_selection(&mut self) {
+        self.selecting = false;
+        if self.range_selection.take().is_some() {
+            self.dirty = true;
+        }
+    }
+
+    // Codepoints inside the rectangular selection, in codepoint order
+    pub fn range_codepoints(&self) -> Vec<u32> {
+        let Some(range) = &self.range_selection else {
+            return Vec::new();
+        };
+        let (min_col, min_row, max_col, max_row) = range.bounds();
+        let plane_start = range.plane * 0x10000;
+        (min_row..=max_row)
+            .flat_map(|row| {
+                (min_col..=max_col).map(move |col| plane_start + row * self.view.grid_cols + col)
+            })
+            .collect()
+    }
+
+    // Text for the clipboard: the rectangular selection if there is one,
+    // otherwise the single selected character. Unassigned cells and
+    // surrogates are skipped rather than becoming replacement characters.
+    pub fn selected_as_string(&self) -> String {
+        let codepoints = if self.range_selection.is_some() {
+            self.range_codepoints()
+        } else {
+            self.selected_codepoint.into_iter().collect()
+        };
+        codepoints
+            .into_iter()
+            .filter(|&cp| is_assigned(cp))
+            .filter_map(char::from_u32)
+            .collect()
+    }
+
+    pub fn set_legend_visible(&mut self, visible: bool) {
+        self.dirty = true;
+        self.legend_visible = visible;
+    }
+
+    pub fn is_legend_visible(&self) -> bool {
+        self.legend_visible
+    }
+
+    // Show two characters side by side over the grid, e.g. Latin 'a' vs Cyrillic 'а'
+    pub fn set_compare(&mut self, a: u32, b: u32) {
+        self.dirty = true;
+        self.compare = Some(ComparePair {
+            a,
+            b,
+            identical_glyphs: a != b && glyphs_render_identically(a, b, &self.font_family).unwrap_or(false),
+            confusable: a != b && confusable_skeleton(a) == confusable_skeleton(b),
+        });
+    }
+
+    pub fn clear_compare(&mut self) {
+        self.dirty = true;
+        self.compare = None;
+    }
+
+    // Tint a block (matched like `find_block`) and outline its edges; an empty
+    // name clears it. Returns false if no block matches.
+    pub fn highlight_block(&mut self, name: &str) -> bool {
+        self.dirty = true;
+        if name.trim().is_empty() {
+            self.highlighted_block = None;
+            return true;
+        }
+        self.highlighted_block = find_block_range(name);
+        self.highlighted_block.is_some()
+    }
+
+    // Toggling pans the view by the strip height so no row ends up hidden under it
+    pub fn set_header_visible(&mut self, visible: bool) {
+        if visible == self.header_visible {
+            return;
+        }
+        let (_, old_top) = self.grid_inset();
+        self.header_visible = visible;
+        let (_, top) = self.grid_inset();
+        self.view.offset_y += top - old_top;
+        self.fitted = false;
+        self.dirty = true;
+    }
+
+    pub fn is_header_visible(&self) -> bool {
+        self.header_visible
+    }
+
+    // Hex column headers along the top and row start codepoints down the left,
+    // so a cell reads as row + column (e.g. 1F600 + 0A = U+1F60A). The bands
+    // stay pinned to the canvas edges while their labels scroll with the grid;
+    // toggling them pans the view by the band size so no cells end up hidden.
+    pub fn set_show_axes(&mut self, show: bool) {
+        if show == self.axes_visible {
+            return;
+        }
+        let (old_left, old_top) = self.grid_inset();
+        self.axes_visible = show;
+        let (left, top) = self.grid_inset();
+        self.view.offset_x += left - old_left;
+        self.view.offset_y += top - old_top;
+        self.fitted = false;
+        self.dirty = true;
+    }
+
+    pub fn is_axes_visible(&self) -> bool {
+        self.axes_visible
+    }
+
+    // "You are here": the block under the middle of the view, drawn in the
+    // bottom-left corner and updated as the view pans
+    pub fn set_show_context(&mut self, show: bool) {
+        self.dirty = true;
+        self.context_visible = show;
+    }
+
+    pub fn is_context_visible(&self) -> bool {
+        self.context_visible
+    }
+
+    // One-sentence summary for screen readers (e.g. an aria-live region):
+    // "Viewing plane 1 (Supplementary Multilingual Plane), columns 32–96,
+    // rows 0–40, selected U+1F600 GRINNING FACE." Ranges are inclusive.
+    pub fn describe_view(&self) -> String {
+        let plane = self.view.plane;
+        let (start_col, start_row, end_col, end_row) = self.view.visible_bounds();
+        let range = if start_col < end_col && start_row < end_row {
+            format!("columns {}–{}, rows {}–{}", start_col, end_col - 1, start_row, end_row - 1)
+        } else {
+            "no cells visible".to_string()
+        };
+        let selection = match self.selected_codepoint {
+            Some(cp) => {
+                let info = get_char_info(cp);
+                format!("selected {} {}", info.codepoint, info.name)
+            }
+            None => "nothing selected".to_string(),
+        };
+        format!("Viewing plane {} ({}), {}, {}.", plane, plane_name(plane), range, selection)
+    }
+
+    // Visible cells as [start_col, start_row, end_col, end_row] (end exclusive),
+    // clamped to the grid exactly as `render` does
+    pub fn visible_range(&self) -> Vec<u32> {
+        let (start_col, start_row, end_col, end_row) = self.view.visible_bounds();
+        vec![start_col, start_row, end_col, end_row]
+    }
+
+    // True when state has changed since the last `render`, so a
+    // requestAnimationFrame loop can skip redundant frames
+    pub fn needs_render(&self) -> bool {
+        self.dirty
+    }
+
+    // Force the next `needs_render` to report true, e.g. after a web font loads
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn render(&mut self) {
+        let started = self.performance.as_ref().map(|p| p.now());
+        self.dirty = false;
+        self.pin_strip();
+        if self.view.zoom < GLYPH_MIN_ZOOM {
+            self.refresh_color_cache();
+        }
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let pad = self.cell_inset(cell_size);
+
+        // Map CSS pixels onto the (possibly HiDPI) backing store
+        let dpr = self.device_pixel_ratio;
+        let _ = ctx.set_transform(dpr, 0.0, 0.0, dpr, 0.0, 0.0);
+
+        // Clear canvas
+        ctx.set_fill_style_str(&self.background_color);
+        ctx.fill_rect(0.0, 0.0, self.view.width, self.view.height);
+
+        // Calculate visible range
+        let (start_col, start_row, end_col, end_row) = self.view.visible_bounds();
+
+        // Set font based on zoom
+        let font_size = (cell_size * 0.6).clamp(8.0, 32.0);
+        ctx.set_font(&format!("{}px {}", font_size, self.font_family));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        let notdef_width = if self.flag_missing_glyphs {
+            ctx.measure_text(NOTDEF_PROBE).ok().map(|m| m.width())
+        } else {
+            None
+        };
+
+        // The cached color map only stands in for fills while zoomed out
+        let cached = self
+            .color_cache
+            .as_ref()
+            .filter(|cache| self.view.zoom < GLYPH_MIN_ZOOM && cache.key == self.color_cache_key());
+
+        // Cells the current filter draws, with their top-left pixel. Nothing
+        // needs them per cell when the cache covers fills and glyphs are off.
+        let mut cells = Vec::new();
+        if cached.is_none() {
+            for row in start_row..end_row {
+                for col in start_col..end_col {
+                    let codepoint = self.view.cell_codepoint(row, col);
+                    // Unassigned cells are left as background when filtering
+                    if !self.assigned_only || is_assigned(codepoint) {
+                        let x = self.view.offset_x + (col as f64 * cell_size);
+                        let y = self.view.offset_y + (row as f64 * cell_size);
+                        cells.push((codepoint, x, y));
+                    }
+                }
+            }
+        }
+
+        // One scaled blit, each map pixel stretched over its cell
+        if let Some(cache) = cached {
+            ctx.set_image_smoothing_enabled(false);
+            let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
+                &cache.canvas,
+                self.view.offset_x,
+                self.view.offset_y,
+                self.view.grid_cols as f64 * cell_size,
+                self.view.grid_rows() as f64 * cell_size,
+            );
+        } else {
+            // Fills are inset and never overlap, so grouping them by color leaves
+            // the output unchanged while switching fill style once per color
+            let mut fills: HashMap<&str, Vec<(f64, f64)>> = HashMap::new();
+            for &(codepoint, x, y) in &cells {
+                fills.entry(self.cell_color(codepoint)).or_default().push((x, y));
+            }
+            for (color, positions) in &fills {
+                ctx.set_fill_style_str(color);
+                for &(x, y) in positions {
+                    ctx.fill_rect(x + pad, y + pad, cell_size - 2.0 * pad, cell_size - 2.0 * pad);
+                }
+            }
+        }
+
+        // Draw characters if zoom is sufficient; hex labels replace them only
+        // from the zoom where the labels start, so no band of zoom goes blank
+        let hex_labels = self.show_hex && self.view.zoom >= HEX_LABEL_ZOOM;
+        if self.view.zoom >= GLYPH_MIN_ZOOM && !hex_labels {
+            ctx.set_fill_style_str("#ffffff");
+            for &(codepoint, x, y) in &cells {
+                let Some(ch) = char::from_u32(codepoint) else {
+                    continue;
+                };
+                // Stand-in symbols are dimmed so they don't pass for real glyphs
+                let symbol = if self.reveal_invisibles { invisible_symbol(ch) } else { None };
+                if let Some(symbol) = symbol {
+                    ctx.set_fill_style_str("#aaaaaa");
+                    let _ = ctx.fill_text(&symbol.to_string(), x + cell_size / 2.0, y + cell_size / 2.0);
+                    ctx.set_fill_style_str("#ffffff");
+                    continue;
+                }
+
+                let text = glyph_text(ch, self.mark_base);
+                let _ = ctx.fill_text(&text, x + cell_size / 2.0, y + cell_size / 2.0);
+
+                if let Some(notdef) = notdef_width {
+                    if is_assigned(codepoint) && self.lacks_glyph(ch, &text, notdef) {
+                        self.render_missing_glyph(x, y, cell_size);
+                    }
+                }
+            }
+        }
+
+        // Veil cells failing the filter; the cached map is dimmed already
+        if self.filter != FilterKind::None && cached.is_none() {
+            ctx.set_global_alpha(FILTER_DIM_ALPHA);
+            ctx.set_fill_style_str(&self.background_color);
+            for &(codepoint, x, y) in &cells {
+                if !self.filter.matches(codepoint) {
+                    ctx.fill_rect(x, y, cell_size, cell_size);
+                }
+            }
+            ctx.set_global_alpha(1.0);
+        }
+
+        // Corner marker on search matches
+        if !self.search_highlights.is_empty() {
+            let size = (cell_size * 0.35).max(3.0);
+            ctx.set_fill_style_str("#e94560");
+            for row in start_row..end_row {
+                for col in start_col..end_col {
+                    if !self.search_highlights.contains(&self.view.cell_codepoint(row, col)) {
+                        continue;
+                    }
+                    let x = self.view.offset_x + (col as f64 * cell_size);
+                    let y = self.view.offset_y + (row as f64 * cell_size);
+                    ctx.begin_path();
+                    ctx.move_to(x + cell_size - pad - size, y + pad);
+                    ctx.line_to(x + cell_size - pad, y + pad);
+                    ctx.line_to(x + cell_size - pad, y + pad + size);
+                    ctx.close_path();
+                    ctx.fill();
+                }
+            }
+        }
+
+        // Outline hovered
+        if let Some((x, y)) = self.hovered_codepoint.and_then(|cp| self.visible_cell_origin(cp)) {
+            ctx.set_stroke_style_str("rgba(255, 255, 255, 0.5)");
+            ctx.set_line_width(1.5);
+            ctx.stroke_rect(x + pad, y + pad, cell_size - 2.0 * pad, cell_size - 2.0 * pad);
+        }
+
+        // Highlight selected
+        if let Some((x, y)) = self.selected_codepoint.and_then(|cp| self.visible_cell_origin(cp)) {
+            ctx.set_stroke_style_str(&self.selection_color);
+            ctx.set_line_width(self.selection_width);
+            ctx.stroke_rect(x + pad, y + pad, cell_size - 2.0 * pad, cell_size - 2.0 * pad);
+        }
+
+        // Translucent overlay for the rectangular selection
+        if let Some(range) = self.range_selection.as_ref().filter(|r| r.plane == self.view.plane) {
+            let (min_col, min_row, max_col, max_row) = range.bounds();
+            let x = self.view.offset_x + min_col as f64 * cell_size;
+            let y = self.view.offset_y + min_row as f64 * cell_size;
+            let w = (max_col - min_col + 1) as f64 * cell_size;
+            let h = (max_row - min_row + 1) as f64 * cell_size;
+            ctx.set_fill_style_str("rgba(255, 204, 0, 0.2)");
+            ctx.fill_rect(x, y, w, h);
+            ctx.set_stroke_style_str("#ffcc00");
+            ctx.set_line_width(1.5);
+            ctx.stroke_rect(x, y, w, h);
+        }
+
+        if hex_labels {
+            self.render_hex_labels(start_col, start_row, end_col, end_row);
+        }
+
+        if self.flag_deprecated && self.view.zoom >= GLYPH_MIN_ZOOM {
+            self.render_deprecated_flags(start_col, start_row, end_col, end_row);
+        }
+
+        if self.mark_wide && self.view.zoom >= GLYPH_MIN_ZOOM {
+            self.render_wide_marks(start_col, start_row, end_col, end_row);
+        }
+
+        self.render_bookmarks();
+
+        if let Some((low, high)) = self.highlighted_block {
+            self.render_block_highlight(low, high, start_col, start_row, end_col, end_row);
+        }
+
+        // Name labels under each glyph when zoomed in far
+        if self.show_names && self.view.zoom >= NAME_LABEL_ZOOM {
+            self.render_names(start_col, start_row, end_col, end_row);
+        }
+
+        // Draw grid lines if zoomed in enough
+        if self.view.zoom >= 1.0 {
+            ctx.set_stroke_style_str("#333355");
+            ctx.set_line_width(0.5);
+            
+            for col in start_col..=end_col {
+                let x = self.view.offset_x + (col as f64 * cell_size);
+                ctx.begin_path();
+                ctx.move_to(x, 0.0);
+                ctx.line_to(x, self.view.height);
+                ctx.stroke();
+            }
+            
+            for row in start_row..=end_row {
+                let y = self.view.offset_y + (row as f64 * cell_size);
+                ctx.begin_path();
+                ctx.move_to(0.0, y);
+                ctx.line_to(self.view.width, y);
+                ctx.stroke();
+            }
+        }
+
+        if self.axes_visible {
+            self.render_axes(start_col, start_row, end_col, end_row);
+        }
+
+        if self.crosshair_visible {
+            self.render_crosshair();
+        }
+
+        // Overlays go last so they stay on top of the grid.
+        // The legend only describes category colors.
+        if self.legend_visible && self.color_mode == ColorMode::Category {
+            self.render_legend();
+        }
+        if self.header_visible {
+            self.render_header();
+        }
+        if self.context_visible {
+            self.render_context();
+        }
+        if self.compare.is_some() {
+            self.render_compare();
+        }
+
+        if let (Some(performance), Some(started)) = (&self.performance, started) {
+            self.last_render_ms = performance.now() - started;
+        }
+    }
+
+    // Wall time of the most recent `render` in milliseconds, or 0 if unavailable
+    pub fn last_render_ms(&self) -> f64 {
+        self.last_render_ms
+    }
+
+    // Draw the whole current plane from the top-left corner at `cell_px` CSS
+    // pixels per cell, colored by the active mode, for sidebar thumbnails.
+    // Zoom and offsets are left untouched; the next `render` redraws the
+    // interactive view.
+    pub fn render_thumbnail(&mut self, cell_px: f64) {
+        if cell_px.is_nan() || cell_px <= 0.0 {
+            return;
+        }
+        self.refresh_color_cache();
+        self.dirty = true;
+
+        let ctx = &self.ctx;
+        let dpr = self.device_pixel_ratio;
+        let _ = ctx.set_transform(dpr, 0.0, 0.0, dpr, 0.0, 0.0);
+        ctx.set_fill_style_str(&self.background_color);
+        ctx.fill_rect(0.0, 0.0, self.view.width, self.view.height);
+
+        let cols = self.view.grid_cols;
+        let rows = self.view.grid_rows();
+        if let Some(cache) = &self.color_cache {
+            ctx.set_image_smoothing_enabled(false);
+            let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
+                &cache.canvas,
+                0.0,
+                0.0,
+                cols as f64 * cell_px,
+                rows as f64 * cell_px,
+            );
+            return;
+        }
+
+        // No offscreen canvas available: fill cell by cell with the same
+        // colors the cache would have baked in
+        let background = parse_hex_color(&self.background_color).unwrap_or([0, 0, 0, 255]);
+        let mut parsed = HashMap::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let codepoint = self.view.cell_codepoint(row, col);
+                let Some([r, g, b, a]) = self.baked_cell_rgba(codepoint, &background, &mut parsed) else {
+                    continue;
+                };
+                ctx.set_fill_style_str(&format!("rgba({}, {}, {}, {})", r, g, b, a as f64 / 255.0));
+                ctx.fill_rect(col as f64 * cell_px, row as f64 * cell_px, cell_px, cell_px);
+            }
+        }
+    }
+
+    // Capture what's currently on the canvas; call after `render`
+    pub fn export_png(&self) -> Result<String, JsValue> {
+        self.canvas.to_data_url()
+    }
+
+    // Same as `export_png` but for other formats, e.g. "image/jpeg" with a 0.0-1.0 quality
+    pub fn export_image(&self, mime_type: &str, quality: Option<f64>) -> Result<String, JsValue> {
+        match quality {
+            Some(q) => self
+                .canvas
+                .to_data_url_with_type_and_encoder_options(mime_type, &JsValue::from_f64(q.clamp(0.0, 1.0))),
+            None => self.canvas.to_data_url_with_type(mime_type),
+        }
+    }
+
+    // JSON snapshot of the view, for session restore or deep links
+    pub fn export_state(&self) -> String {
+        json_object(&[
+            ("offset_x", json_number(self.view.offset_x)),
+            ("offset_y", json_number(self.view.offset_y)),
+            ("zoom", json_number(self.view.zoom)),
+            ("plane", self.view.plane.to_string()),
+            (
+                "selected_codepoint",
+                self.selected_codepoint
+                    .map(|cp| cp.to_string())
+                    .unwrap_or_else(|| "null".to_string()),
+            ),
+            (
+                "bookmarks",
+                format!(
+                    "[{}]",
+                    self.bookmarks.iter().map(u32::to_string).collect::<Vec<_>>().join(",")
+                ),
+            ),
+        ])
+    }
+
+    // Restore a snapshot from `export_state`. Zoom is clamped to the usual range;
+    // anything else out of range rejects the whole snapshot.
+    pub fn import_state(&mut self, json: &str) -> Result<(), JsValue> {
+        let state = js_sys::JSON::parse(json)?;
+        if !state.is_object() {
+            return Err(JsValue::from_str("state must be a JSON object"));
+        }
+
+        let number = |key: &str| -> Result<f64, JsValue> {
+            js_sys::Reflect::get(&state, &JsValue::from_str(key))?
+                .as_f64()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| JsValue::from_str(&format!("missing or invalid \"{}\"", key)))
+        };
+
+        let offset_x = number("offset_x")?;
+        let offset_y = number("offset_y")?;
+        let zoom = number("zoom")?;
+        let plane = number("plane")?;
+        if plane.fract() != 0.0 || !(0.0..=16.0).contains(&plane) {
+            return Err(JsValue::from_str("plane must be an integer from 0 to 16"));
+        }
+
+        let selected = js_sys::Reflect::get(&state, &JsValue::from_str("selected_codepoint"))?;
+        let selected = if selected.is_null() || selected.is_undefined() {
+            None
+        } else {
+            match selected.as_f64() {
+                Some(cp) if cp.fract() == 0.0 && (0.0..=MAX_CODEPOINT as f64).contains(&cp) => {
+                    Some(cp as u32)
+                }
+                _ => return Err(JsValue::from_str("selected_codepoint must be a valid codepoint or null")),
+            }
+        };
+
+        // Optional so snapshots from before bookmarks existed still load
+        let bookmarks = js_sys::Reflect::get(&state, &JsValue::from_str("bookmarks"))?;
+        let bookmarks = if bookmarks.is_null() || bookmarks.is_undefined() {
+            Vec::new()
+        } else if js_sys::Array::is_array(&bookmarks) {
+            let mut parsed = Vec::new();
+            for item in js_sys::Array::from(&bookmarks).iter() {
+                match item.as_f64() {
+                    Some(cp) if cp.fract() == 0.0 && (0.0..=MAX_CODEPOINT as f64).contains(&cp) => {
+                        if !parsed.contains(&(cp as u32)) {
+                            parsed.push(cp as u32);
+                        }
+                    }
+                    _ => return Err(JsValue::from_str("bookmarks must be an array of valid codepoints")),
+                }
+            }
+            parsed
+        } else {
+            return Err(JsValue::from_str("bookmarks must be an array of valid codepoints"));
+        };
+
+        self.animation = None;
+        self.fitted = false;
+        self.bookmarks = bookmarks;
+        self.view.offset_x = offset_x;
+        self.view.offset_y = offset_y;
+        self.view.zoom = zoom.clamp(0.1, 10.0);
+        self.view.plane = plane as u32;
+        self.dirty = true;
+        self.set_selection(selected);
+        Ok(())
+    }
+
+    // Overlay panel with the compare pair's glyphs, names and codepoints
+    pub fn render_compare(&self) {
+        let Some(pair) = &self.compare else {
+            return;
+        };
+        let ctx = &self.ctx;
+
+        let (panel_x, panel_y, panel_width, panel_height) = self.draw_center_panel();
+        let glyph_size = (panel_height * 0.4).min(panel_width * 0.25);
+        let label_size = (panel_width / 40.0).clamp(10.0, 14.0);
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        for (i, &codepoint) in [pair.a, pair.b].iter().enumerate() {
+            let center_x = panel_x + panel_width * (0.25 + 0.5 * i as f64);
+            let glyph_y = panel_y + panel_height * 0.4;
+
+            ctx.set_fill_style_str("#ffffff");
+            ctx.set_font(&format!("{}px {}", glyph_size, self.font_family));
+            if let Some(ch) = char::from_u32(codepoint) {
+                let _ = ctx.fill_text(&glyph_text(ch, self.mark_base), center_x, glyph_y);
+            }
+
+            let info = get_char_info(codepoint);
+            ctx.set_font(&format!("{}px sans-serif", label_size));
+            let name = self.fit_text(&info.name, panel_width * 0.45);
+            let _ = ctx.fill_text(&name, center_x, panel_y + panel_height * 0.72);
+            ctx.set_fill_style_str("#aaaaaa");
+            let _ = ctx.fill_text(&info.codepoint, center_x, panel_y + panel_height * 0.72 + label_size * 1.6);
+        }
+
+        let verdict = match (pair.identical_glyphs, pair.confusable) {
+            (true, _) => Some("Glyphs render identically"),
+            (false, true) => Some("Confusable: same skeleton"),
+            (false, false) => None,
+        };
+        if let Some(verdict) = verdict {
+            ctx.set_fill_style_str("#e94560");
+            ctx.set_font(&format!("{}px sans-serif", label_size));
+            let _ = ctx.fill_text(
+                verdict,
+                panel_x + panel_width / 2.0,
+                panel_y + panel_height - label_size * 1.5,
+            );
+        }
+    }
+
+    // Draw `base` alone and followed by a variation selector side by side,
+    // e.g. U+2764 with VS15 (text) vs VS16 (emoji). Drawn straight onto the
+    // canvas like the other overlays, so call it after `render`.
+    pub fn render_with_variation(&self, base: u32, selector: u32) {
+        let ctx = &self.ctx;
+        let Some(base_char) = char::from_u32(base) else {
+            return;
+        };
+
+        let (panel_x, panel_y, panel_width, panel_height) = self.draw_center_panel();
+        let glyph_size = (panel_height * 0.4).min(panel_width * 0.25);
+        let label_size = (panel_width / 40.0).clamp(10.0, 14.0);
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        let sequence: String = std::iter::once(base_char).chain(char::from_u32(selector)).collect();
+        let selector_label = if is_variation_selector(selector) {
+            format!("U+{:04X} + {}", base, variation_selector_name(selector))
+        } else {
+            format!("U+{:04X} + U+{:04X} (not a variation selector)", base, selector)
+        };
+        let panes = [
+            (base_char.to_string(), format!("U+{:04X}", base)),
+            (sequence, selector_label),
+        ];
+        for (i, (text, label)) in panes.iter().enumerate() {
+            let center_x = panel_x + panel_width * (0.25 + 0.5 * i as f64);
+
+            ctx.set_fill_style_str("#ffffff");
+            ctx.set_font(&format!("{}px {}", glyph_size, self.font_family));
+            let _ = ctx.fill_text(text, center_x, panel_y + panel_height * 0.4);
+
+            ctx.set_fill_style_str("#aaaaaa");
+            ctx.set_font(&format!("{}px sans-serif", label_size));
+            let label = self.fit_text(label, panel_width * 0.45);
+            let _ = ctx.fill_text(&label, center_x, panel_y + panel_height * 0.75);
+        }
+    }
+
+    // Preview `codepoints` as one line of vertical text, top to bottom,
+    // drawn in the panel `render_compare` uses; call it after `render`.
+    // Wide characters such as CJK stay upright; everything else (Latin,
+    // Mongolian, ...) is turned 90° clockwise, approximating UAX #50.
+    // Characters that don't fit the panel height are dropped.
+    pub fn render_vertical(&self, codepoints: &[u32]) {
+        let chars: Vec<char> = codepoints.iter().filter_map(|&cp| char::from_u32(cp)).collect();
+        if chars.is_empty() {
+            return;
+        }
+        let ctx = &self.ctx;
+        let (panel_x, panel_y, panel_width, panel_height) = self.draw_center_panel();
+        let padding = panel_height * 0.05;
+        let glyph_size = ((panel_height - 2.0 * padding) / chars.len() as f64)
+            .min(panel_width * 0.25)
+            .max(8.0);
+        let fitting = ((panel_height - 2.0 * padding) / glyph_size).floor() as usize;
+
+        ctx.set_fill_style_str("#ffffff");
+        ctx.set_font(&format!("{}px {}", glyph_size, self.font_family));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        let center_x = panel_x + panel_width / 2.0;
+        for (i, ch) in chars.iter().take(fitting).enumerate() {
+            let center_y = panel_y + padding + (i as f64 + 0.5) * glyph_size;
+            let text = ch.to_string();
+            if is_wide(*ch as u32) {
+                let _ = ctx.fill_text(&text, center_x, center_y);
+            } else {
+                ctx.save();
+                let _ = ctx.translate(center_x, center_y);
+                let _ = ctx.rotate(std::f64::consts::FRAC_PI_2);
+                let _ = ctx.fill_text(&text, 0.0, 0.0);
+                ctx.restore();
+            }
+        }
+    }
+
+    // Top strip naming the current plane and its codepoint range
+    pub fn render_header(&self) {
+        let ctx = &self.ctx;
+        let font_size = (self.view.width / 80.0).clamp(11.0, 15.0);
+        let strip_height = self.header_height();
+        let start = self.view.plane * 0x10000;
+
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.85)");
+        ctx.fill_rect(0.0, 0.0, self.view.width, strip_height);
+        ctx.set_stroke_style_str("#333355");
+        ctx.set_line_width(1.0);
+        ctx.begin_path();
+        ctx.move_to(0.0, strip_height);
+        ctx.line_to(self.view.width, strip_height);
+        ctx.stroke();
+
+        ctx.set_font(&format!("{}px sans-serif", font_size));
+        ctx.set_text_baseline("middle");
+        ctx.set_fill_style_str("#ffffff");
+        ctx.set_text_align("left");
+        let title = format!("Plane {} — {}", self.view.plane, plane_name(self.view.plane));
+        let _ = ctx.fill_text(&title, font_size * 0.75, strip_height / 2.0);
+
+        ctx.set_fill_style_str("#aaaaaa");
+        ctx.set_text_align("right");
+        let range = format!("U+{:04X}–U+{:04X}", start, start + 0xFFFF);
+        let _ = ctx.fill_text(&range, self.view.width - font_size * 0.75, strip_height / 2.0);
+    }
+
+    pub fn render_legend(&self) {
+        let ctx = &self.ctx;
+        let entries = legend_entries(&self.color_overrides);
+
+        // Scale with canvas width so the legend stays readable on small screens
+        let font_size = (self.view.width / 80.0).clamp(10.0, 14.0);
+        ctx.set_font(&format!("{}px sans-serif", font_size));
+        let line_height = font_size * 1.6;
+        let swatch = font_size;
+        let padding = font_size * 0.75;
+        // Wide enough for the longest label, which overrides can add
+        let label_width = entries
+            .iter()
+            .filter_map(|(_, label)| ctx.measure_text(label).ok())
+            .map(|metrics| metrics.width())
+            .fold(0.0, f64::max);
+        let panel_width = (font_size * 11.0).max(padding * 2.0 + swatch * 1.6 + label_width);
+        let panel_height = padding * 2.0 + line_height * entries.len() as f64;
+        let panel_x = self.view.width - panel_width - padding;
+        let panel_y = self.view.height - panel_height - padding;
+
+        // Panel background
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.85)");
+        ctx.fill_rect(panel_x, panel_y, panel_width, panel_height);
+        ctx.set_stroke_style_str("#333355");
+        ctx.set_line_width(1.0);
+        ctx.stroke_rect(panel_x, panel_y, panel_width, panel_height);
+
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("middle");
+
+        for (i, (color, label)) in entries.iter().enumerate() {
+            let row_y = panel_y + padding + line_height * i as f64;
+            let center_y = row_y + line_height / 2.0;
+
+            ctx.set_fill_style_str(color);
+            ctx.fill_rect(panel_x + padding, center_y - swatch / 2.0, swatch, swatch);
+
+            ctx.set_fill_style_str("#ffffff");
+            let _ = ctx.fill_text(label, panel_x + padding + swatch * 1.6, center_y);
+        }
+    }
+
+    // Codepoints above U+10FFFF are ignored
+    pub fn center_on(&mut self, codepoint: u32) {
+        if !self.view.center_on(codepoint) {
+            return;
+        }
+        self.dirty = true;
+        self.animation = None;
+        self.fitted = false;
+    }
+
+    // Glide to a codepoint over `duration_ms`; drive it by calling `tick` each frame.
+    // Cross-plane jumps switch plane halfway through. Codepoints above U+10FFFF
+    // are ignored.
+    pub fn animate_center_on(&mut self, codepoint: u32, duration_ms: f64) {
+        if codepoint > MAX_CODEPOINT {
+            return;
+        }
+        if duration_ms.is_nan() || duration_ms <= 0.0 {
+            self.center_on(codepoint);
+            return;
+        }
+
+        self.fitted = false;
+        self.animation = Some(CenterAnimation {
+            from: self.view.view_center(),
+            to: self.view.cell_center(codepoint),
+            from_zoom: self.view.zoom,
+            to_zoom: self.view.zoom,
+            target_plane: codepoint / 0x10000,
+            duration_ms,
+            start_ms: None,
+        });
+    }
+
+    // Zoom to `target_zoom` (clamped like `set_zoom`) with the selected cell
+    // centered, or around the canvas midpoint when nothing is selected.
+    // Animates through `tick` like `animate_center_on`; a non-positive
+    // duration applies it at once.
+    pub fn zoom_to_selection(&mut self, target_zoom: f64, duration_ms: f64) {
+        if target_zoom.is_nan() {
+            return;
+        }
+        let (to, target_plane) = match self.selected_codepoint {
+            Some(cp) => (self.view.cell_center(cp), cp / 0x10000),
+            None => (self.view.view_center(), self.view.plane),
+        };
+
+        self.dirty = true;
+        self.fitted = false;
+        self.animation = Some(CenterAnimation {
+            from: self.view.view_center(),
+            to,
+            from_zoom: self.view.zoom,
+            to_zoom: target_zoom.clamp(0.1, 10.0),
+            target_plane,
+            duration_ms: duration_ms.max(0.0),
+            start_ms: None,
+        });
+        if duration_ms.is_nan() || duration_ms <= 0.0 {
+            self.tick(0.0);
+        }
+    }
+
+    // Advance the running animation to `now_ms` (e.g. the requestAnimationFrame
+    // timestamp). Returns true while the animation is still running.
+    pub fn tick(&mut self, now_ms: f64) -> bool {
+        let Some(anim) = self.animation.as_mut() else {
+            return false;
+        };
+
+        self.dirty = true;
+        let start_ms = *anim.start_ms.get_or_insert(now_ms);
+        let t = if anim.duration_ms > 0.0 {
+            ((now_ms - start_ms) / anim.duration_ms).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let eased = ease_in_out(t);
+
+        // Geometric zoom steps feel even, matching the multiplicative wheel zoom
+        self.view.zoom = anim.from_zoom * (anim.to_zoom / anim.from_zoom).powf(eased);
+        self.view.center_at(
+            anim.from.0 + (anim.to.0 - anim.from.0) * eased,
+            anim.from.1 + (anim.to.1 - anim.from.1) * eased,
+        );
+        if t >= 0.5 {
+            self.view.plane = anim.target_plane;
+        }
+
+        if t >= 1.0 {
+            self.animation = None;
+            false
+        } else {
+            true
+        }
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    // Returns false if the codepoint is invalid or already bookmarked
+    pub fn add_bookmark(&mut self, codepoint: u32) -> bool {
+        if codepoint > MAX_CODEPOINT || self.bookmarks.contains(&codepoint) {
+            return false;
+        }
+        self.bookmarks.push(codepoint);
+        self.dirty = true;
+        true
+    }
+
+    pub fn remove_bookmark(&mut self, codepoint: u32) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|&cp| cp != codepoint);
+        let removed = self.bookmarks.len() != before;
+        self.dirty |= removed;
+        removed
+    }
+
+    pub fn list_bookmarks(&self) -> Vec<u32> {
+        self.bookmarks.clone()
+    }
+
+    // Load results to step through; the cursor starts before the first one
+    pub fn set_results(&mut self, codepoints: &[u32]) {
+        self.results = codepoints.to_vec();
+        self.result_cursor = None;
+    }
+
+    // Whether `next_result` / `prev_result` wrap around at the ends
+    pub fn set_wrap_results(&mut self, wrap: bool) {
+        self.wrap_results = wrap;
+    }
+
+    pub fn next_result(&mut self) -> Option<u32> {
+        let len = self.results.len();
+        let index = match self.result_cursor {
+            None if len > 0 => 0,
+            Some(i) if i + 1 < len => i + 1,
+            Some(_) if self.wrap_results => 0,
+            _ => return None,
+        };
+        Some(self.go_to_result(index))
+    }
+
+    pub fn prev_result(&mut self) -> Option<u32> {
+        let len = self.results.len();
+        let index = match self.result_cursor {
+            None if len > 0 => len - 1,
+            Some(i) if i > 0 => i - 1,
+            Some(_) if self.wrap_results => len - 1,
+            _ => return None,
+        };
+        Some(self.go_to_result(index))
+    }
+
+    // JSON counts of assigned characters in the current plane, by general
+    // category: {"plane":1,"assigned":N,"categories":{"OtherSymbol":N,...}}.
+    // Categories are named as in `get_char_info`; empty ones are left out.
+    pub fn plane_stats(&self) -> String {
+        plane_stats_json(self.view.plane).to_string()
+    }
+
+    // Select and center a uniformly random assigned character
+    pub fn random_assigned(&mut self) -> u32 {
+        let index = assigned_runs();
+        let pick = (js_sys::Math::random() * index.total as f64) as u32;
+        let codepoint = index.nth(pick.min(index.total - 1));
+        self.center_on(codepoint);
+        self.set_selection(Some(codepoint));
+        codepoint
+    }
+}
+
+// Pan, zoom and the grid <-> pixel mapping, kept free of any canvas types so
+// the geometry can be exercised natively
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewState {
+    // Logical canvas size in CSS pixels
+    width: f64,
+    height: f64,
+    offset_x: f64,
+    offset_y: f64,
+    zoom: f64,
+    // Plane (0 = BMP, 1-16 for supplementary)
+    plane: u32,
+    // Columns per row; always divides 0x10000 evenly
+    grid_cols: u32,
+}
+
+impl ViewState {
+    pub fn new(width: f64, height: f64) -> ViewState {
+        ViewState {
+            width,
+            height,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            zoom: 1.0,
+            plane: 0,
+            grid_cols: GRID_SIZE,
+        }
+    }
+
+    // Take a new CSS-pixel size and return the canvas backing store size in
+    // device pixels. Only the backing store scales; the view stays in CSS pixels.
+    pub fn resize(&mut self, width: f64, height: f64, device_pixel_ratio: f64) -> (u32, u32) {
+        self.width = width;
+        self.height = height;
+        (
+            (width * device_pixel_ratio).round() as u32,
+            (height * device_pixel_ratio).round() as u32,
+        )
+    }
+
+    // Zoom and center so the whole plane fits in the area right of `left`
+    // and below `top`
+    pub fn fit(&mut self, left: f64, top: f64) {
+        let cols = self.grid_cols as f64;
+        let rows = self.grid_rows() as f64;
+        let width = (self.width - left).max(0.0);
+        let height = (self.height - top).max(0.0);
+        self.zoom = (width / (cols * CELL_SIZE))
+            .min(height / (rows * CELL_SIZE))
+            .clamp(0.1, 10.0);
+
+        let cell_size = CELL_SIZE * self.zoom;
+        self.offset_x = left + (width - cols * cell_size) / 2.0;
+        self.offset_y = top + (height - rows * cell_size) / 2.0;
+    }
+
+    // Change zoom while keeping the grid point under (x, y) fixed on screen
+    pub fn zoom_around(&mut self, x: f64, y: f64, zoom: f64) {
+        // Grid-space point under the anchor, captured before the zoom changes
+        let cell_size = CELL_SIZE * self.zoom;
+        let world_x = (x - self.offset_x) / cell_size;
+        let world_y = (y - self.offset_y) / cell_size;
+
+        self.zoom = zoom.clamp(0.1, 10.0);
+
+        // Re-derive the offset so the same grid point maps back to the anchor
+        let cell_size = CELL_SIZE * self.zoom;
+        self.offset_x = x - world_x * cell_size;
+        self.offset_y = y - world_y * cell_size;
+    }
+
+    // Codepoint under a pixel, or None anywhere outside the grid.
+    // Bounds are checked in floating point before any integer cast, so large
+    // pans in either direction can't wrap or saturate into a valid cell.
+    pub fn codepoint_at(&self, x: f64, y: f64) -> Option<u32> {
+        let cell_size = CELL_SIZE * self.zoom;
+        let grid_x = ((x - self.offset_x) / cell_size).floor();
+        let grid_y = ((y - self.offset_y) / cell_size).floor();
+        if (0.0..self.grid_cols as f64).contains(&grid_x)
+            && (0.0..self.grid_rows() as f64).contains(&grid_y)
+        {
+            Some(self.cell_codepoint(grid_y as u32, grid_x as u32))
+        } else {
+            None
+        }
+    }
+
+    // Offsets that put a codepoint's cell in the middle of the view
+    pub fn center_offsets(&self, codepoint: u32) -> (f64, f64) {
+        let (row, col) = self.cell_of(codepoint);
+
+        let cell_size = CELL_SIZE * self.zoom;
+        (
+            self.width / 2.0 - (col as f64 + 0.5) * cell_size,
+            self.height / 2.0 - (row as f64 + 0.5) * cell_size,
+        )
+    }
+
+    // Switch to the codepoint's plane with its cell in the middle of the view;
+    // returns false, leaving the view alone, for codepoints above U+10FFFF
+    pub fn center_on(&mut self, codepoint: u32) -> bool {
+        if codepoint > MAX_CODEPOINT {
+            return false;
+        }
+        let (offset_x, offset_y) = self.center_offsets(codepoint);
+        self.plane = codepoint / 0x10000;
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+        true
+    }
+
+    // Point at the middle of the view, in cells from the grid's top-left
+    pub fn view_center(&self) -> (f64, f64) {
+        let cell_size = CELL_SIZE * self.zoom;
+        (
+            (self.width / 2.0 - self.offset_x) / cell_size,
+            (self.height / 2.0 - self.offset_y) / cell_size,
+        )
+    }
+
+    // Pan so the grid point (in cells) lands in the middle of the view
+    pub fn center_at(&mut self, col: f64, row: f64) {
+        let cell_size = CELL_SIZE * self.zoom;
+        self.offset_x = self.width / 2.0 - col * cell_size;
+        self.offset_y = self.height / 2.0 - row * cell_size;
+    }
+
+    // Middle of a codepoint's cell, in cells
+    pub fn cell_center(&self, codepoint: u32) -> (f64, f64) {
+        let (row, col) = self.cell_of(codepoint);
+        (col as f64 + 0.5, row as f64 + 0.5)
+    }
+
+    // Pan the minimum distance needed to show the whole cell; returns true if it moved
+    pub fn scroll_into_view(&mut self, col: u32, row: u32) -> bool {
+        let cell_size = CELL_SIZE * self.zoom;
+        let x = self.offset_x + col as f64 * cell_size;
+        let y = self.offset_y + row as f64 * cell_size;
+
+        let (old_x, old_y) = (self.offset_x, self.offset_y);
+
+        if x < 0.0 {
+            self.offset_x -= x;
+        } else if x + cell_size > self.width {
+            self.offset_x -= x + cell_size - self.width;
+        }
+
+        if y < 0.0 {
+            self.offset_y -= y;
+        } else if y + cell_size > self.height {
+            self.offset_y -= y + cell_size - self.height;
+        }
+
+        self.offset_x != old_x || self.offset_y != old_y
+    }
+
+    // Visible cells as (start_col, start_row, end_col, end_row), end exclusive
+    pub fn visible_bounds(&self) -> (u32, u32, u32, u32) {
+        let cell_size = CELL_SIZE * self.zoom;
+        // Starts clamp to the grid too, so a view panned past the far edge gives an empty range
+        let start_col = ((-self.offset_x) / cell_size).floor().clamp(0.0, self.grid_cols as f64) as u32;
+        let start_row = ((-self.offset_y) / cell_size).floor().clamp(0.0, self.grid_rows() as f64) as u32;
+        let end_col = ((self.width - self.offset_x) / cell_size).ceil().min(self.grid_cols as f64) as u32;
+        let end_row = ((self.height - self.offset_y) / cell_size).ceil().min(self.grid_rows() as f64) as u32;
+        (start_col, start_row, end_col, end_row)
+    }
+
+    // Cell under a pixel, clamped to the grid edges so drags past the border still count
+    pub fn clamped_cell_at(&self, x: f64, y: f64) -> (u32, u32) {
+        let cell_size = CELL_SIZE * self.zoom;
+        let col = ((x - self.offset_x) / cell_size).floor().clamp(0.0, (self.grid_cols - 1) as f64);
+        let row = ((y - self.offset_y) / cell_size).floor().clamp(0.0, (self.grid_rows() - 1) as f64);
+        (col as u32, row as u32)
+    }
+
+    pub fn grid_rows(&self) -> u32 {
+        0x10000 / self.grid_cols
+    }
+
+    // Codepoint at a cell of the current plane
+    pub fn cell_codepoint(&self, row: u32, col: u32) -> u32 {
+        (self.plane * 0x10000) + (row * self.grid_cols) + col
+    }
+
+    // (row, col) of a codepoint within its own plane
+    pub fn cell_of(&self, codepoint: u32) -> (u32, u32) {
+        let local = codepoint % 0x10000;
+        (local / self.grid_cols, local % self.grid_cols)
+    }
+
+    // (row, col) of a codepoint in the displayed plane, or None if it lives elsewhere
+    pub fn grid_position(&self, codepoint: u32) -> Option<(u32, u32)> {
+        if codepoint > MAX_CODEPOINT || codepoint / 0x10000 != self.plane {
+            return None;
+        }
+        Some(self.cell_of(codepoint))
+    }
+}
+
+struct ComparePair {
+    a: u32,
+    b: u32,
+    // Pixel-identical in the compare font, computed once in `set_compare`
+    identical_glyphs: bool,
+    // Same confusables skeleton (UTS #39), independent of the font
+    confusable: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ColorCacheKey {
+    plane: u32,
+    grid_cols: u32,
+    color_mode: ColorMode,
+    assigned_only: bool,
+    filter: FilterKind,
+    palette_version: u32,
+}
+
+// A plane's cell colors at one pixel per cell, valid while `key` matches
+struct PlaneColorCache {
+    key: ColorCacheKey,
+    canvas: HtmlCanvasElement,
+}
+
+// Detached canvas for offscreen drawing
+fn scratch_canvas(width: u32, height: u32) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), JsValue> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let ctx = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    Ok((canvas, ctx))
+}
+
+// Draw both glyphs on a scratch canvas and compare the pixels
+fn glyphs_render_identically(a: u32, b: u32, font_family: &str) -> Result<bool, JsValue> {
+    const SIZE: u32 = 64;
+    let (_canvas, ctx) = scratch_canvas(SIZE, SIZE)?;
+    ctx.set_font(&format!("{}px {}", SIZE * 3 / 4, font_family));
+    ctx.set_text_align("center");
+    ctx.set_text_baseline("middle");
+
+    let rasterize = |codepoint: u32| -> Result<Vec<u8>, JsValue> {
+        ctx.clear_rect(0.0, 0.0, SIZE as f64, SIZE as f64);
+        if let Some(ch) = char::from_u32(codepoint) {
+            ctx.fill_text(&ch.to_string(), SIZE as f64 / 2.0, SIZE as f64 / 2.0)?;
+        }
+        Ok(ctx.get_image_data(0.0, 0.0, SIZE as f64, SIZE as f64)?.data().0)
+    };
+
+    let pixels_a = rasterize(a)?;
+    let blank = pixels_a.iter().all(|&p| p == 0);
+    Ok(!blank && pixels_a == rasterize(b)?)
+}
+
+// Rectangle in grid space, as (col, row) corners in any order
+struct RangeSelection {
+    plane: u32,
+    anchor: (u32, u32),
+    cursor: (u32, u32),
+}
+
+impl RangeSelection {
+    // (min_col, min_row, max_col, max_row), inclusive
+    fn bounds(&self) -> (u32, u32, u32, u32) {
+        (
+            self.anchor.0.min(self.cursor.0),
+            self.anchor.1.min(self.cursor.1),
+            self.anchor.0.max(self.cursor.0),
+            self.anchor.1.max(self.cursor.1),
+        )
+    }
+}
+
+// In-flight pinch gesture
+struct Pinch {
+    start_distance: f64,
+    start_zoom: f64,
+    // Finger midpoint at the previous `pinch_move`
+    mid_x: f64,
+    mid_y: f64,
+}
+
+// In-flight `animate_center_on` / `zoom_to_selection` state. The view center
+// moves in grid cells rather than pixels, so it stays put while zoom changes.
+struct CenterAnimation {
+    from: (f64, f64),
+    to: (f64, f64),
+    from_zoom: f64,
+    to_zoom: f64,
+    target_plane: u32,
+    duration_ms: f64,
+    // Taken from the first `tick` so callers can use any clock
+    start_ms: Option<f64>,
+}
+
+// Cubic ease-in-out over t in [0, 1]
+fn ease_in_out(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+impl UnicodeExplorer {
+    // Backdrop shared by the compare and variation previews; returns (x, y, width, height)
+    fn draw_center_panel(&self) -> (f64, f64, f64, f64) {
+        let ctx = &self.ctx;
+        let panel_width = (self.view.width * 0.8).min(640.0);
+        let panel_height = (self.view.height * 0.6).min(360.0);
+        let panel_x = (self.view.width - panel_width) / 2.0;
+        let panel_y = (self.view.height - panel_height) / 2.0;
+
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.92)");
+        ctx.fill_rect(panel_x, panel_y, panel_width, panel_height);
+        ctx.set_stroke_style_str("#333355");
+        ctx.set_line_width(1.0);
+        ctx.stroke_rect(panel_x, panel_y, panel_width, panel_height);
+        (panel_x, panel_y, panel_width, panel_height)
+    }
+
+    fn header_height(&self) -> f64 {
+        (self.view.width / 80.0).clamp(11.0, 15.0) * 2.0
+    }
+
+    // Space the overlays cover at the top-left, as (left, top): the header
+    // strip when shown, plus the axis bands below it when shown
+    fn grid_inset(&self) -> (f64, f64) {
+        let header = if self.header_visible { self.header_height() } else { 0.0 };
+        if self.axes_visible {
+            (AXIS_BAND_LEFT, header + AXIS_BAND_TOP)
+        } else {
+            (0.0, header)
+        }
+    }
+
+    // Row and column header bands; labels thin out so they never overlap
+    fn render_axes(&self, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let top = if self.header_visible { self.header_height() } else { 0.0 };
+        let plane_start = self.view.plane * 0x10000;
+
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.85)");
+        ctx.fill_rect(0.0, top, self.view.width, AXIS_BAND_TOP);
+        ctx.fill_rect(0.0, top, AXIS_BAND_LEFT, self.view.height - top);
+
+        ctx.set_font(&format!("{}px monospace", AXIS_FONT_SIZE));
+        ctx.set_fill_style_str("#aaaaaa");
+        ctx.set_text_baseline("middle");
+
+        // Power-of-two label strides keep labels on round hex offsets
+        let stride = |min_spacing: f64| {
+            let mut step = 1;
+            while (step as f64) * cell_size < min_spacing {
+                step *= 2;
+            }
+            step
+        };
+
+        ctx.set_text_align("center");
+        let col_step = stride(AXIS_FONT_SIZE * 2.0);
+        for col in (start_col..end_col).filter(|col| col % col_step == 0) {
+            let x = self.view.offset_x + (col as f64 + 0.5) * cell_size;
+            if x > AXIS_BAND_LEFT {
+                let _ = ctx.fill_text(&format!("{:02X}", col), x, top + AXIS_BAND_TOP / 2.0);
+            }
+        }
+
+        ctx.set_text_align("right");
+        let row_step = stride(AXIS_FONT_SIZE * 1.5);
+        for row in (start_row..end_row).filter(|row| row % row_step == 0) {
+            let y = self.view.offset_y + (row as f64 + 0.5) * cell_size;
+            if y > top + AXIS_BAND_TOP {
+                let label = format!("{:04X}", plane_start + row * self.view.grid_cols);
+                let _ = ctx.fill_text(&label, AXIS_BAND_LEFT - 6.0, y);
+            }
+        }
+    }
+
+    // Nothing is drawn while the view center is off the grid
+    fn render_context(&self) {
+        use unic_ucd_block::Block;
+
+        let Some(codepoint) = self.view.codepoint_at(self.view.width / 2.0, self.view.height / 2.0) else {
+            return;
+        };
+        let block = char::from_u32(codepoint)
+            .and_then(Block::of)
+            .map_or("No block", |block| block.name);
+        let ctx = &self.ctx;
+        let font_size = (self.view.width / 80.0).clamp(10.0, 14.0);
+        let padding = font_size * 0.75;
+        let label = format!("{} · U+{:04X}", block, codepoint);
+
+        ctx.set_font(&format!("{}px sans-serif", font_size));
+        let text_width = ctx.measure_text(&label).map(|m| m.width()).unwrap_or(0.0);
+        let box_height = font_size * 2.0;
+        let box_y = self.view.height - box_height - padding;
+
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.85)");
+        ctx.fill_rect(padding, box_y, text_width + padding * 2.0, box_height);
+        ctx.set_fill_style_str("#ffffff");
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("middle");
+        let _ = ctx.fill_text(&label, padding * 2.0, box_y + box_height / 2.0);
+    }
+
+    // Hidden while the cursor is off the grid
+    fn render_crosshair(&self) {
+        let Some((x, y)) = self.cursor else {
+            return;
+        };
+        let Some(codepoint) = self.view.codepoint_at(x, y) else {
+            return;
+        };
+        let ctx = &self.ctx;
+
+        ctx.set_stroke_style_str("rgba(255, 255, 255, 0.35)");
+        ctx.set_line_width(1.0);
+        ctx.begin_path();
+        ctx.move_to(x, 0.0);
+        ctx.line_to(x, self.view.height);
+        ctx.move_to(0.0, y);
+        ctx.line_to(self.view.width, y);
+        ctx.stroke();
+
+        // Readout below-right of the cursor, flipped near the right/bottom edges
+        let label = format!("U+{:04X}", codepoint);
+        ctx.set_font(&format!("{}px monospace", AXIS_FONT_SIZE));
+        let text_width = ctx.measure_text(&label).map(|m| m.width()).unwrap_or(0.0);
+        let box_width = text_width + 8.0;
+        let box_height = AXIS_FONT_SIZE + 6.0;
+        let box_x = if x + 12.0 + box_width > self.view.width { x - 12.0 - box_width } else { x + 12.0 };
+        let box_y = if y + 12.0 + box_height > self.view.height { y - 12.0 - box_height } else { y + 12.0 };
+
+        ctx.set_fill_style_str("rgba(16, 16, 32, 0.85)");
+        ctx.fill_rect(box_x, box_y, box_width, box_height);
+        ctx.set_fill_style_str("#ffffff");
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("middle");
+        let _ = ctx.fill_text(&label, box_x + 4.0, box_y + box_height / 2.0);
+    }
+
+    // Star in the top-left corner of each visible bookmarked cell
+    fn render_bookmarks(&self) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let size = (cell_size * 0.35).max(6.0);
+
+        ctx.set_font(&format!("{}px sans-serif", size));
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("top");
+        ctx.set_fill_style_str("#ffcc00");
+        for &codepoint in &self.bookmarks {
+            if let Some((x, y)) = self.visible_cell_origin(codepoint) {
+                let _ = ctx.fill_text("\u{2605}", x + 2.0, y + 2.0);
+            }
+        }
+    }
+
+    // Everything the cached color map depends on
+    fn color_cache_key(&self) -> ColorCacheKey {
+        ColorCacheKey {
+            plane: self.view.plane,
+            grid_cols: self.view.grid_cols,
+            color_mode: self.color_mode,
+            assigned_only: self.assigned_only,
+            filter: self.filter,
+            palette_version: self.palette_version,
+        }
+    }
+
+    // Rebuild the color map if its key no longer matches; on failure the cache
+    // is dropped and `render` falls back to per-cell fills
+    fn refresh_color_cache(&mut self) {
+        let key = self.color_cache_key();
+        if self.color_cache.as_ref().is_some_and(|cache| cache.key == key) {
+            return;
+        }
+        self.color_cache = self.build_color_cache(key).ok();
+    }
+
+    // One pixel per cell; cells the filter hides stay transparent
+    fn build_color_cache(&self, key: ColorCacheKey) -> Result<PlaneColorCache, JsValue> {
+        let cols = self.view.grid_cols;
+        let rows = self.view.grid_rows();
+        let mut parsed = HashMap::new();
+        let mut pixels = vec![0u8; (cols * rows * 4) as usize];
+        let background = parse_hex_color(&self.background_color).unwrap_or([0, 0, 0, 255]);
+        for row in 0..rows {
+            for col in 0..cols {
+                let codepoint = self.view.cell_codepoint(row, col);
+                if let Some(pixel) = self.baked_cell_rgba(codepoint, &background, &mut parsed) {
+                    let i = ((row * cols + col) * 4) as usize;
+                    pixels[i..i + 4].copy_from_slice(&pixel);
+                }
+            }
+        }
+
+        let (canvas, ctx) = scratch_canvas(cols, rows)?;
+        let image = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&pixels),
+            cols,
+            rows,
+        )?;
+        ctx.put_image_data(&image, 0.0, 0.0)?;
+        Ok(PlaneColorCache { key, canvas })
+    }
+
+    // A cell's color with the filter dimming blended in, or None for cells
+    // `assigned_only` hides. `parsed` memoizes hex parsing across calls.
+    fn baked_cell_rgba<'a>(
+        &'a self,
+        codepoint: u32,
+        background: &[u8; 4],
+        parsed: &mut HashMap<&'a str, [u8; 4]>,
+    ) -> Option<[u8; 4]> {
+        if self.assigned_only && !is_assigned(codepoint) {
+            return None;
+        }
+        let color = self.cell_color(codepoint);
+        let mut pixel = *parsed
+            .entry(color)
+            .or_insert_with(|| parse_hex_color(color).unwrap_or([0, 0, 0, 0]));
+        if !self.filter.matches(codepoint) {
+            // Same blend as the veil `render` lays over per-cell fills
+            for (channel, &bg) in pixel.iter_mut().zip(background).take(3) {
+                *channel = (*channel as f64 * (1.0 - FILTER_DIM_ALPHA) + bg as f64 * FILTER_DIM_ALPHA) as u8;
+            }
+        }
+        Some(pixel)
+    }
+
+    // `cell_padding`, limited to half the cell so fills never turn negative
+    fn cell_inset(&self, cell_size: f64) -> f64 {
+        self.cell_padding.min(cell_size / 2.0)
+    }
+
+    // Top-left pixel of a codepoint's cell if it is within the visible bounds
+    fn visible_cell_origin(&self, codepoint: u32) -> Option<(f64, f64)> {
+        if codepoint > MAX_CODEPOINT || codepoint / 0x10000 != self.view.plane {
+            return None;
+        }
+        let (row, col) = self.view.cell_of(codepoint);
+        let (start_col, start_row, end_col, end_row) = self.view.visible_bounds();
+        if !(start_col..end_col).contains(&col) || !(start_row..end_row).contains(&row) {
+            return None;
+        }
+        let cell_size = CELL_SIZE * self.view.zoom;
+        Some((
+            self.view.offset_x + col as f64 * cell_size,
+            self.view.offset_y + row as f64 * cell_size,
+        ))
+    }
+
+    fn go_to_plane(&mut self, plane: u32) {
+        self.set_plane(plane);
+        self.fit_plane();
+    }
+
+    fn go_to_result(&mut self, index: usize) -> u32 {
+        let codepoint = self.results[index];
+        self.result_cursor = Some(index);
+        self.center_on(codepoint);
+        self.set_selection(Some(codepoint));
+        codepoint
+    }
+
+    // Tint cells in [low, high] and stroke the edges they share with cells outside it
+    fn render_block_highlight(&self, low: u32, high: u32, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let in_block = |row: i64, col: i64| {
+            if row < 0 || col < 0 || row >= self.view.grid_rows() as i64 || col >= self.view.grid_cols as i64 {
+                return false;
+            }
+            (low..=high).contains(&self.view.cell_codepoint(row as u32, col as u32))
+        };
+
+        ctx.set_fill_style_str("rgba(100, 180, 255, 0.2)");
+        ctx.set_stroke_style_str("#64b4ff");
+        ctx.set_line_width(2.0);
+        ctx.begin_path();
+        for row in start_row..end_row {
+            for col in start_col..end_col {
+                let (r, c) = (row as i64, col as i64);
+                if !in_block(r, c) {
+                    continue;
+                }
+                let x = self.view.offset_x + col as f64 * cell_size;
+                let y = self.view.offset_y + row as f64 * cell_size;
+                ctx.fill_rect(x, y, cell_size, cell_size);
+
+                let edges = [
+                    (!in_block(r - 1, c), x, y, x + cell_size, y),
+                    (!in_block(r + 1, c), x, y + cell_size, x + cell_size, y + cell_size),
+                    (!in_block(r, c - 1), x, y, x, y + cell_size),
+                    (!in_block(r, c + 1), x + cell_size, y, x + cell_size, y + cell_size),
+                ];
+                for (outside, x1, y1, x2, y2) in edges {
+                    if outside {
+                        ctx.move_to(x1, y1);
+                        ctx.line_to(x2, y2);
+                    }
+                }
+            }
+        }
+        ctx.stroke();
+    }
+
+    fn render_hex_labels(&self, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+
+        // Monospace digits are ~0.6em wide, so four of them fill 2.4em
+        let font_size = ((cell_size - 4.0) / 2.4).min(cell_size * 0.5).min(24.0);
+        ctx.set_font(&format!("{}px monospace", font_size));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        ctx.set_fill_style_str("#ffffff");
+
+        for row in start_row..end_row {
+            for col in start_col..end_col {
+                let codepoint = self.view.cell_codepoint(row, col);
+                if self.assigned_only && !is_assigned(codepoint) {
+                    continue;
+                }
+                let x = self.view.offset_x + (col as f64 * cell_size);
+                let y = self.view.offset_y + (row as f64 * cell_size);
+                let label = format!("{:04X}", codepoint & 0xFFFF);
+                let _ = ctx.fill_text(&label, x + cell_size / 2.0, y + cell_size / 2.0);
+            }
+        }
+    }
+
+    fn render_deprecated_flags(&self, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let pad = self.cell_inset(cell_size) + 1.0;
+        let dash = js_sys::Array::of2(&JsValue::from_f64(3.0), &JsValue::from_f64(2.0));
+
+        ctx.set_stroke_style_str("#ff5555");
+        ctx.set_line_width(1.5);
+        let _ = ctx.set_line_dash(&dash);
+        for row in start_row..end_row {
+            for col in start_col..end_col {
+                let codepoint = self.view.cell_codepoint(row, col);
+                if !is_noncharacter(codepoint) && !is_deprecated(codepoint) {
+                    continue;
+                }
+                let x = self.view.offset_x + (col as f64 * cell_size);
+                let y = self.view.offset_y + (row as f64 * cell_size);
+                ctx.stroke_rect(x + pad, y + pad, cell_size - 2.0 * pad, cell_size - 2.0 * pad);
+            }
+        }
+        let _ = ctx.set_line_dash(&js_sys::Array::new());
+    }
+
+    // Faint bar along the bottom of each double-width cell
+    fn render_wide_marks(&self, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let pad = self.cell_inset(cell_size);
+        let bar = (cell_size * 0.08).max(1.0);
+
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.3)");
+        for row in start_row..end_row {
+            for col in start_col..end_col {
+                let codepoint = self.view.cell_codepoint(row, col);
+                if !is_wide(codepoint) || (self.assigned_only && !is_assigned(codepoint)) {
+                    continue;
+                }
+                let x = self.view.offset_x + (col as f64 * cell_size);
+                let y = self.view.offset_y + (row as f64 * cell_size);
+                ctx.fill_rect(x + pad, y + cell_size - pad - bar, cell_size - 2.0 * pad, bar);
+            }
+        }
+    }
+
+    fn render_names(&self, start_col: u32, start_row: u32, end_col: u32, end_row: u32) {
+        let ctx = &self.ctx;
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let max_width = cell_size - 6.0;
+
+        ctx.set_font(&format!("{}px sans-serif", (cell_size * 0.11).clamp(7.0, 14.0)));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("bottom");
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.7)");
+
+        for row in start_row..end_row {
+            for col in start_col..end_col {
+                let codepoint = self.view.cell_codepoint(row, col);
+                if self.assigned_only && !is_assigned(codepoint) {
+                    continue;
+                }
+                let name = char::from_u32(codepoint)
+                    .and_then(unicode_names2::name)
+                    .map(|name| name.to_string())
+                    .or_else(|| pua_label(codepoint));
+                let Some(name) = name else {
+                    continue;
+                };
+
+                let label = self.fit_text(&name, max_width);
+                let x = self.view.offset_x + (col as f64 * cell_size);
+                let y = self.view.offset_y + (row as f64 * cell_size);
+                let _ = ctx.fill_text(&label, x + cell_size / 2.0, y + cell_size - 3.0);
+            }
+        }
+    }
+
+    // Truncate with an ellipsis until the text fits `max_width` in the current font
+    fn fit_text(&self, text: &str, max_width: f64) -> String {
+        let fits = |t: &str| {
+            self.ctx
+                .measure_text(t)
+                .map(|m| m.width() <= max_width)
+                .unwrap_or(true)
+        };
+        if fits(text) {
+            return text.to_string();
+        }
+
+        let mut chars: Vec<char> = text.chars().collect();
+        while !chars.is_empty() {
+            chars.pop();
+            let candidate: String = chars.iter().chain(std::iter::once(&'…')).collect();
+            if fits(&candidate) {
+                return candidate;
+            }
+        }
+        String::new()
+    }
+
+    // Heuristic behind `set_flag_missing_glyphs`; expects the glyph font to be set
+    fn lacks_glyph(&self, ch: char, text: &str, notdef_width: f64) -> bool {
+        use unic_ucd_category::GeneralCategory;
+
+        let Ok(metrics) = self.ctx.measure_text(text) else {
+            return false;
+        };
+        let width = metrics.width();
+        if (width - notdef_width).abs() < 0.01 {
+            return true;
+        }
+        // Controls, format characters and line breaks legitimately have no advance
+        let invisible = matches!(
+            GeneralCategory::of(ch),
+            GeneralCategory::Control
+                | GeneralCategory::Format
+                | GeneralCategory::LineSeparator
+                | GeneralCategory::ParagraphSeparator
+        );
+        !invisible && width < 0.01
+    }
+
+    // Diagonal stripes over a cell flagged as missing its glyph
+    fn render_missing_glyph(&self, x: f64, y: f64, cell_size: f64) {
+        let ctx = &self.ctx;
+        ctx.save();
+        ctx.begin_path();
+        let pad = self.cell_inset(cell_size);
+        ctx.rect(x + pad, y + pad, cell_size - 2.0 * pad, cell_size - 2.0 * pad);
+        ctx.clip();
+
+        ctx.set_stroke_style_str("rgba(233, 69, 96, 0.6)");
+        ctx.set_line_width((cell_size / 16.0).max(1.0));
+        ctx.begin_path();
+        let spacing = cell_size / 4.0;
+        for i in 1..8 {
+            let d = i as f64 * spacing;
+            ctx.move_to(x + d, y);
+            ctx.line_to(x + d - cell_size, y + cell_size);
+        }
+        ctx.stroke();
+        ctx.restore();
+    }
+
+    // All selection changes go through here so `on_select` fires exactly once per change
+    fn set_selection(&mut self, codepoint: Option<u32>) {
+        if self.selected_codepoint == codepoint {
+            return;
+        }
+        if let Some(cp) = codepoint {
+            self.push_history(cp);
+        }
+        self.apply_selection(codepoint);
+    }
+
+    // New selections drop any forward entries, like browser navigation
+    fn push_history(&mut self, codepoint: u32) {
+        if self.history.get(self.history_index) == Some(&codepoint) {
+            return;
+        }
+        self.history.truncate(self.history_index + 1);
+        self.history.push(codepoint);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.history_index = self.history.len() - 1;
+    }
+
+    fn go_to_history(&mut self, index: usize) -> u32 {
+        let codepoint = self.history[index];
+        self.history_index = index;
+        self.center_on(codepoint);
+        self.apply_selection(Some(codepoint));
+        codepoint
+    }
+
+    // Selection change that leaves history alone
+    fn apply_selection(&mut self, codepoint: Option<u32>) {
+        if self.selected_codepoint == codepoint {
+            return;
+        }
+
+        self.selected_codepoint = codepoint;
+        self.dirty = true;
+        // Queued as a microtask rather than called inline: a synchronous call
+        // would run while JS still holds the `&mut self` borrow of this method's
+        // caller, so any explorer call from the callback would panic with a
+        // recursive-borrow error. Errors report as uncaught exceptions.
+        if let (Some(callback), Some(window)) = (&self.on_select, web_sys::window()) {
+            let arg = codepoint.map(JsValue::from).unwrap_or(JsValue::NULL);
+            let notify: js_sys::Function = callback.bind1(&JsValue::NULL, &arg).unchecked_into();
+            window.queue_microtask(&notify);
+        }
+    }
+
+    // Pan the minimum distance needed to show the whole cell
+    fn scroll_into_view(&mut self, col: u32, row: u32) {
+        if self.view.scroll_into_view(col, row) {
+            self.fitted = false;
+            self.dirty = true;
+        }
+    }
+
+    fn zoom_around(&mut self, x: f64, y: f64, zoom: f64) {
+        self.dirty = true;
+        self.fitted = false;
+        self.view.zoom_around(x, y, zoom);
+        self.pin_strip();
+        self.clamp_pan();
+    }
+
+    // Apply `pan_bounds`: pull the offsets back just far enough
+    fn clamp_pan(&mut self) {
+        if !self.pan_bounds {
+            return;
+        }
+        let cell_size = CELL_SIZE * self.view.zoom;
+        let clamp = |offset: f64, grid: f64, view: f64| {
+            let keep = PAN_MIN_VISIBLE.min(grid).min(view);
+            offset.clamp(keep - grid, view - keep)
+        };
+        let offset_x = clamp(self.view.offset_x, self.view.grid_cols as f64 * cell_size, self.view.width);
+        let offset_y = clamp(self.view.offset_y, self.view.grid_rows() as f64 * cell_size, self.view.height);
+        if (offset_x, offset_y) != (self.view.offset_x, self.view.offset_y) {
+            self.view.offset_x = offset_x;
+            self.view.offset_y = offset_y;
+            self.dirty = true;
+        }
+    }
+
+    // The strip only pans horizontally, so hold it vertically centered
+    fn pin_strip(&mut self) {
+        if self.layout == Layout::Strip {
+            self.view.offset_y = (self.view.height - CELL_SIZE * self.view.zoom) / 2.0;
+        }
+    }
+
+    fn cell_color(&self, codepoint: u32) -> &str {
+        use unic_ucd_category::GeneralCategory;
+
+        match self.color_mode {
+            ColorMode::Category => char::from_u32(codepoint)
+                .map(GeneralCategory::of)
+                .and_then(|cat| self.color_overrides.get(&cat))
+                .map(String::as_str)
+                .unwrap_or_else(|| get_category_color(codepoint)),
+            ColorMode::Script => get_script_color(codepoint),
+            // Unassigned cells blend into the background so the byte-length
+            // boundaries stand out
+            ColorMode::Utf8Length => char::from_u32(codepoint)
+                .filter(|_| is_assigned(codepoint))
+                .map(|ch| UTF8_LENGTH_PALETTE[ch.len_utf8() - 1])
+                .unwrap_or(&self.background_color),
+            ColorMode::Age => get_age_color(codepoint).unwrap_or(&self.background_color),
+        }
+    }
+
+}
+
+// Visible stand-in for a character that draws nothing, per `set_reveal_invisibles`
+fn invisible_symbol(ch: char) -> Option<char> {
+    use unic_ucd_category::GeneralCategory;
+
+    match GeneralCategory::of(ch) {
+        GeneralCategory::SpaceSeparator => Some('\u{2423}'), // OPEN BOX
+        GeneralCategory::ParagraphSeparator => Some('\u{00B6}'), // PILCROW SIGN
+        GeneralCategory::LineSeparator => Some('\u{23CE}'), // RETURN SYMBOL
+        GeneralCategory::Control => match ch as u32 {
+            cp @ 0x00..=0x1F => char::from_u32(0x2400 + cp),
+            0x7F => Some('\u{2421}'), // SYMBOL FOR DELETE
+            _ => Some('\u{2B1A}'), // DOTTED SQUARE
+        },
+        GeneralCategory::Format => Some('\u{2B1A}'),
+        _ => None,
+    }
+}
+
+fn get_category_color(codepoint: u32) -> &'static str {
+    use unic_ucd_category::GeneralCategory;
+    
+    if let Some(ch) = char::from_u32(codepoint) {
+        category_color(GeneralCategory::of(ch))
+    } else {
+        COLOR_UNASSIGNED // Invalid codepoint
+    }
+}
+
+fn category_color(cat: unic_ucd_category::GeneralCategory) -> &'static str {
+    use unic_ucd_category::GeneralCategory;
+
+    match cat {
+        GeneralCategory::UppercaseLetter |
+        GeneralCategory::LowercaseLetter |
+        GeneralCategory::TitlecaseLetter |
+        GeneralCategory::ModifierLetter |
+        GeneralCategory::OtherLetter => COLOR_LETTERS,
+        
+        GeneralCategory::DecimalNumber |
+        GeneralCategory::LetterNumber |
+        GeneralCategory::OtherNumber => COLOR_NUMBERS,
+        
+        GeneralCategory::SpaceSeparator |
+        GeneralCategory::LineSeparator |
+        GeneralCategory::ParagraphSeparator => COLOR_WHITESPACE,
+        
+        GeneralCategory::Control |
+        GeneralCategory::Format |
+        GeneralCategory::Surrogate |
+        GeneralCategory::PrivateUse => COLOR_CONTROL,
+        
+        GeneralCategory::ConnectorPunctuation |
+        GeneralCategory::DashPunctuation |
+        GeneralCategory::OpenPunctuation |
+        GeneralCategory::ClosePunctuation |
+        GeneralCategory::InitialPunctuation |
+        GeneralCategory::FinalPunctuation |
+        GeneralCategory::OtherPunctuation => COLOR_PUNCTUATION,
+        
+        GeneralCategory::MathSymbol |
+        GeneralCategory::CurrencySymbol |
+        GeneralCategory::ModifierSymbol |
+        GeneralCategory::OtherSymbol => COLOR_SYMBOLS,
+        
+        GeneralCategory::NonspacingMark |
+        GeneralCategory::SpacingMark |
+        GeneralCategory::EnclosingMark => COLOR_MARKS,
+        
+        GeneralCategory::Unassigned => COLOR_UNASSIGNED,
+    }
+}
+
+// Legend rows as (color, label), following `set_category_color` overrides.
+// A group whose categories all share one override takes that color; otherwise
+// the group keeps its default swatch (while any of its categories still use
+// it) and each overridden category gets a row of its own below it.
+fn legend_entries(overrides: &HashMap<unic_ucd_category::GeneralCategory, String>) -> Vec<(String, String)> {
+    use unic_char_property::EnumeratedCharProperty;
+    use unic_ucd_category::GeneralCategory;
+
+    let mut entries = Vec::new();
+    for &(color, label) in LEGEND.iter() {
+        let members: Vec<GeneralCategory> = GeneralCategory::all_values()
+            .iter()
+            .copied()
+            .filter(|&cat| category_color(cat) == color)
+            .collect();
+        let overridden: Vec<(GeneralCategory, &String)> = members
+            .iter()
+            .filter_map(|cat| overrides.get(cat).map(|hex| (*cat, hex)))
+            .collect();
+
+        let shared = overridden.first().map(|&(_, hex)| hex).filter(|&hex| {
+            overridden.len() == members.len() && overridden.iter().all(|&(_, other)| other == hex)
+        });
+        if let Some(hex) = shared {
+            entries.push((hex.clone(), label.to_string()));
+            continue;
+        }
+        if overridden.len() < members.len() {
+            entries.push((color.to_string(), label.to_string()));
+        }
+        for (cat, hex) in overridden {
+            entries.push((hex.clone(), cat.long_name().replace('_', " ")));
+        }
+    }
+    entries
+}
+
+fn parse_category(name: &str) -> Option<unic_ucd_category::GeneralCategory> {
+    use unic_char_property::EnumeratedCharProperty;
+    use unic_ucd_category::GeneralCategory;
+
+    GeneralCategory::all_values().iter().copied().find(|cat| {
+        name.eq_ignore_ascii_case(cat.abbr_name())
+            || name.eq_ignore_ascii_case(cat.long_name())
+            || name.eq_ignore_ascii_case(&format!("{:?}", cat))
+    })
+}
+
+// Accepts #rgb, #rgba, #rrggbb and #rrggbbaa
+fn is_valid_hex_color(hex: &str) -> bool {
+    match hex.strip_prefix('#') {
+        Some(digits) => {
+            matches!(digits.len(), 3 | 4 | 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => false,
+    }
+}
+
+// RGBA bytes of a color accepted by `is_valid_hex_color`
+fn parse_hex_color(hex: &str) -> Option<[u8; 4]> {
+    if !is_valid_hex_color(hex) {
+        return None;
+    }
+    let digits: Vec<u8> = hex[1..]
+        .chars()
+        .map(|c| c.to_digit(16).unwrap() as u8)
+        .collect();
+    let channels: Vec<u8> = match digits.len() {
+        // Short forms repeat each digit: #abc == #aabbcc
+        3 | 4 => digits.iter().map(|&d| d * 17).collect(),
+        _ => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+    };
+    Some([channels[0], channels[1], channels[2], channels.get(3).copied().unwrap_or(255)])
+}
+
+fn get_script_color(codepoint: u32) -> &'static str {
+    use unicode_script::{Script, UnicodeScript};
+
+    match char::from_u32(codepoint).map(|ch| ch.script()) {
+        Some(Script::Common) => COLOR_SCRIPT_COMMON,
+        Some(Script::Inherited) => COLOR_SCRIPT_INHERITED,
+        Some(Script::Unknown) | None => COLOR_UNASSIGNED,
+        Some(script) => {
+            // Mix the tag bits so neighbouring tags don't share adjacent slots
+            let hash = script.as_iso15924_tag().wrapping_mul(0x9E37_79B1) >> 16;
+            SCRIPT_PALETTE[hash as usize % SCRIPT_PALETTE.len()]
+        }
+    }
+}
+
+// Gradient slot for the version a character was introduced in
+fn get_age_color(codepoint: u32) -> Option<&'static str> {
+    use unic_ucd_age::Age;
+
+    let version = Age::of(char::from_u32(codepoint)?)?.actual();
+    let ordinal = AGE_VERSIONS
+        .iter()
+        .position(|&(major, minor)| major == version.major && minor == version.minor)
+        .unwrap_or(AGE_VERSIONS.len() - 1);
+    Some(age_palette()[ordinal].as_str())
+}
+
+fn age_palette() -> &'static [String] {
+    static PALETTE: OnceLock<Vec<String>> = OnceLock::new();
+    PALETTE.get_or_init(|| {
+        let steps = (AGE_VERSIONS.len() - 1) as f64;
+        let mix = |from: u8, to: u8, t: f64| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        (0..AGE_VERSIONS.len())
+            .map(|i| {
+                let t = i as f64 / steps;
+                let (r0, g0, b0) = AGE_COLOR_OLDEST;
+                let (r1, g1, b1) = AGE_COLOR_NEWEST;
+                format!("#{:02x}{:02x}{:02x}", mix(r0, r1, t), mix(g0, g1, t), mix(b0, b1, t))
+            })
+            .collect()
+    })
+}
+
+// Surrogates have no `char` representation, so they count as unassigned
+// everywhere the grid filters on assignment
+fn is_assigned(codepoint: u32) -> bool {
+    use unic_ucd_category::GeneralCategory;
+
+    char::from_u32(codepoint)
+        .map(|ch| GeneralCategory::of(ch) != GeneralCategory::Unassigned)
+        .unwrap_or(false)
+}
+
+// Contiguous runs of assigned codepoints, so random picks and counts don't
+// need to rescan (or reject their way through) sparse planes
+struct AssignedRuns {
+    // (first codepoint, assigned codepoints before this run)
+    runs: Vec<(u32, u32)>,
+    total: u32,
+}
+
+impl AssignedRuns {
+    fn build() -> AssignedRuns {
+        let mut runs = Vec::new();
+        let mut total = 0;
+        let mut in_run = false;
+        for cp in 0..=MAX_CODEPOINT {
+            let assigned = is_assigned(cp);
+            if assigned && !in_run {
+                runs.push((cp, total));
+            }
+            if assigned {
+                total += 1;
+            }
+            in_run = assigned;
+        }
+        AssignedRuns { runs, total }
+    }
+
+    // The n-th assigned codepoint (0-based) in codepoint order
+    fn nth(&self, n: u32) -> u32 {
+        let run = self.runs.partition_point(|&(_, before)| before <= n) - 1;
+        let (start, before) = self.runs[run];
+        start + (n - before)
+    }
+
+    // Assigned codepoints strictly below `codepoint`
+    fn count_below(&self, codepoint: u32) -> u32 {
+        let run = self.runs.partition_point(|&(start, _)| start < codepoint);
+        if run == 0 {
+            return 0;
+        }
+        let (start, before) = self.runs[run - 1];
+        let run_len = self.runs.get(run).map_or(self.total, |&(_, next)| next) - before;
+        before + (codepoint - start).min(run_len)
+    }
+}
+
+fn assigned_runs() -> &'static AssignedRuns {
+    static RUNS: OnceLock<AssignedRuns> = OnceLock::new();
+    RUNS.get_or_init(AssignedRuns::build)
+}
+
+// Assigned codepoints across all of Unicode (surrogates and unassigned
+// excluded, private use included), out of 0x110000. The first call scans
+// every codepoint; later calls are cached.
+#[wasm_bindgen]
+pub fn assigned_count() -> u32 {
+    assigned_runs().total
+}
+
+// Same as `assigned_count` for a single plane; 0 for planes past 16
+#[wasm_bindgen]
+pub fn assigned_count_in_plane(plane: u32) -> u32 {
+    if plane > 16 {
+        return 0;
+    }
+    let runs = assigned_runs();
+    runs.count_below((plane + 1) * 0x10000) - runs.count_below(plane * 0x10000)
+}
+
+// Assigned characters per general category. Plane contents never change, so
+// each plane is scanned at most once. Planes above 16 are empty.
+fn plane_category_counts(plane: u32) -> &'static HashMap<unic_ucd_category::GeneralCategory, u32> {
+    use unic_ucd_category::GeneralCategory;
+
+    static COUNTS: [OnceLock<HashMap<GeneralCategory, u32>>; 17] = [const { OnceLock::new() }; 17];
+    static EMPTY: OnceLock<HashMap<GeneralCategory, u32>> = OnceLock::new();
+    let Some(cell) = COUNTS.get(plane as usize) else {
+        return EMPTY.get_or_init(HashMap::new);
+    };
+    cell.get_or_init(|| {
+        let mut counts = HashMap::new();
+        let start = plane * 0x10000;
+        for cp in (start..start + 0x10000).filter(|&cp| is_assigned(cp)) {
+            if let Some(ch) = char::from_u32(cp) {
+                *counts.entry(GeneralCategory::of(ch)).or_default() += 1;
+            }
+        }
+        counts
+    })
+}
+
+fn plane_stats_json(plane: u32) -> &'static str {
+    use unic_char_property::EnumeratedCharProperty;
+    use unic_ucd_category::GeneralCategory;
+
+    static STATS: [OnceLock<String>; 17] = [const { OnceLock::new() }; 17];
+    let Some(cell) = STATS.get(plane as usize) else {
+        return "{}";
+    };
+    cell.get_or_init(|| {
+        let counts = plane_category_counts(plane);
+        let categories: Vec<(String, String)> = GeneralCategory::all_values()
+            .iter()
+            .filter_map(|cat| counts.get(cat).map(|n| (format!("{:?}", cat), n.to_string())))
+            .collect();
+        let fields: Vec<(&str, String)> = categories
+            .iter()
+            .map(|(name, n)| (name.as_str(), n.clone()))
+            .collect();
+        json_object(&[
+            ("plane", plane.to_string()),
+            ("assigned", counts.values().sum::<u32>().to_string()),
+            ("categories", json_object(&fields)),
+        ])
+    })
+}
+
+// Contrast two planes' assigned counts and category distributions as JSON:
+// {"a":0,"b":1,"assigned":{"a":N,"b":N,"difference":N},
+//  "categories":{"OtherSymbol":{"a":N,"b":N,"difference":N},...}}
+// where difference is b - a. Categories empty in both planes are left out.
+// Shares the per-plane cache with `plane_stats`.
+#[wasm_bindgen]
+pub fn compare_planes(a: u32, b: u32) -> Result<String, JsValue> {
+    use unic_char_property::EnumeratedCharProperty;
+    use unic_ucd_category::GeneralCategory;
+
+    if a > 16 || b > 16 {
+        return Err(JsValue::from_str("planes must be integers from 0 to 16"));
+    }
+    let (counts_a, counts_b) = (plane_category_counts(a), plane_category_counts(b));
+    let contrast = |count_a: u32, count_b: u32| {
+        json_object(&[
+            ("a", count_a.to_string()),
+            ("b", count_b.to_string()),
+            ("difference", (count_b as i64 - count_a as i64).to_string()),
+        ])
+    };
+
+    let categories: Vec<(String, String)> = GeneralCategory::all_values()
+        .iter()
+        .filter_map(|cat| {
+            let (count_a, count_b) = (counts_a.get(cat).copied(), counts_b.get(cat).copied());
+            (count_a.is_some() || count_b.is_some()).then(|| {
+                (format!("{:?}", cat), contrast(count_a.unwrap_or(0), count_b.unwrap_or(0)))
+            })
+        })
+        .collect();
+    let fields: Vec<(&str, String)> = categories
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+
+    Ok(json_object(&[
+        ("a", a.to_string()),
+        ("b", b.to_string()),
+        ("assigned", contrast(counts_a.values().sum(), counts_b.values().sum())),
+        ("categories", json_object(&fields)),
+    ]))
+}
+
+// Closest assigned codepoint by codepoint distance, preferring the lower one
+// on a tie; `codepoint` itself if it is assigned. Looks at most 0x400 either
+// way, so None means only a large unassigned gap surrounds it.
+#[wasm_bindgen]
+pub fn nearest_assigned(codepoint: u32) -> Option<u32> {
+    if codepoint > MAX_CODEPOINT {
+        return None;
+    }
+    (0..=SNAP_MAX_DISTANCE).find_map(|distance| {
+        let below = codepoint.checked_sub(distance).filter(|&cp| is_assigned(cp));
+        below.or_else(|| Some(codepoint + distance).filter(|&cp| is_assigned(cp)))
+    })
+}
+
+// Find the first assigned codepoint after `from`, skipping unassigned gaps
+#[wasm_bindgen]
+pub fn next_assigned(from: u32) -> Option<u32> {
+    (from.saturating_add(1)..=MAX_CODEPOINT).find(|&cp| is_assigned(cp))
+}
+
+// Character details, exposed to JS through typed getters
+#[wasm_bindgen]
+pub struct CharInfo {
+    codepoint: String,
+    character: Option<String>,
+    name: String,
+    category: String,
+    block: String,
+    // Case mappings; None when the character maps to itself
+    uppercase: Option<String>,
+    lowercase: Option<String>,
+    titlecase: Option<String>,
+    // Encoded forms as uppercase hex, e.g. ["F0", "9F", "98", "80"] and ["D83D", "DE00"]
+    utf8: Vec<String>,
+    utf16: Vec<String>,
+    // Unicode version that introduced the character, e.g. "6.0"
+    age: Option<String>,
+    combining_class: u8,
+    // Normalized forms as "U+XXXX" sequences
+    nfd: Vec<String>,
+    nfc: Vec<String>,
+    // Bidi_Mirrored, and the Bidi_Mirroring_Glyph codepoint if one is defined
+    mirrored: bool,
+    mirror_of: Option<u32>,
+    numeric_value: Option<String>,
+    // East_Asian_Width abbreviation: N, Na, A, W, F or H
+    east_asian_width: String,
+    // UAX #14 Line_Break class, e.g. "AL"
+    line_break: String,
+    // `is_noncharacter` and `is_deprecated`
+    noncharacter: bool,
+    deprecated: bool,
+    // `is_whitespace` and `is_unicode_whitespace`
+    whitespace: bool,
+    unicode_whitespace: bool,
+    // Leading/vowel/trailing jamo of a precomposed Hangul syllable
+    jamo: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl CharInfo {
+    #[wasm_bindgen(getter)]
+    pub fn codepoint(&self) -> String {
+        self.codepoint.clone()
+    }
+
+    #[wasm_bindgen(getter = char)]
+    pub fn character(&self) -> Option<String> {
+        self.character.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> String {
+        self.category.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn block(&self) -> String {
+        self.block.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn uppercase(&self) -> Option<String> {
+        self.uppercase.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn lowercase(&self) -> Option<String> {
+        self.lowercase.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn titlecase(&self) -> Option<String> {
+        self.titlecase.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn utf8(&self) -> Vec<String> {
+        self.utf8.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn utf16(&self) -> Vec<String> {
+        self.utf16.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn age(&self) -> Option<String> {
+        self.age.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn combining_class(&self) -> u8 {
+        self.combining_class
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nfd(&self) -> Vec<String> {
+        self.nfd.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nfc(&self) -> Vec<String> {
+        self.nfc.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mirrored(&self) -> bool {
+        self.mirrored
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mirror_of(&self) -> Option<u32> {
+        self.mirror_of
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn numeric_value(&self) -> Option<String> {
+        self.numeric_value.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn east_asian_width(&self) -> String {
+        self.east_asian_width.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn line_break(&self) -> String {
+        self.line_break.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn noncharacter(&self) -> bool {
+        self.noncharacter
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn whitespace(&self) -> bool {
+        self.whitespace
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn unicode_whitespace(&self) -> bool {
+        self.unicode_whitespace
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn jamo(&self) -> Vec<String> {
+        self.jamo.clone()
+    }
+
+    pub fn to_json(&self) -> String {
+        json_object(&[
+            ("codepoint", json_string(&self.codepoint)),
+            ("char", json_opt_string(self.character.as_deref())),
+            ("name", json_string(&self.name)),
+            ("category", json_string(&self.category)),
+            ("block", json_string(&self.block)),
+            ("uppercase", json_opt_string(self.uppercase.as_deref())),
+            ("lowercase", json_opt_string(self.lowercase.as_deref())),
+            ("titlecase", json_opt_string(self.titlecase.as_deref())),
+            ("utf8", json_string_array(&self.utf8)),
+            ("utf16", json_string_array(&self.utf16)),
+            ("age", json_opt_string(self.age.as_deref())),
+            ("combining_class", self.combining_class.to_string()),
+            ("nfd", json_string_array(&self.nfd)),
+            ("nfc", json_string_array(&self.nfc)),
+            ("mirrored", self.mirrored.to_string()),
+            (
+                "mirror_of",
+                self.mirror_of
+                    .map(|cp| cp.to_string())
+                    .unwrap_or_else(|| "null".to_string()),
+            ),
+            ("numeric_value", json_opt_string(self.numeric_value.as_deref())),
+            ("east_asian_width", json_string(&self.east_asian_width)),
+            ("line_break", json_string(&self.line_break)),
+            ("noncharacter", self.noncharacter.to_string()),
+            ("deprecated", self.deprecated.to_string()),
+            ("whitespace", self.whitespace.to_string()),
+            ("unicode_whitespace", self.unicode_whitespace.to_string()),
+            ("jamo", json_string_array(&self.jamo)),
+        ])
+    }
+}
+
+// Helper function to get character info
+#[wasm_bindgen]
+pub fn get_char_info(codepoint: u32) -> CharInfo {
+    use unic_ucd_category::GeneralCategory;
+    use unic_ucd_block::Block;
+    use unicode_normalization::UnicodeNormalization;
+    
+    let hex = format!("U+{:04X}", codepoint);
+    
+    if let Some(ch) = char::from_u32(codepoint) {
+        let name = unicode_names2::name(ch)
+            .map(|n| n.to_string())
+            .or_else(|| pua_label(codepoint))
+            .unwrap_or_else(|| "<unnamed>".to_string());
+        
+        let category = GeneralCategory::of(ch);
+        let block = Block::of(ch)
+            .map(|b| b.name.to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        
+        CharInfo {
+            codepoint: hex,
+            character: Some(ch.to_string()),
+            name,
+            category: format!("{:?}", category),
+            block,
+            uppercase: non_identity(ch, ch.to_uppercase().collect()),
+            lowercase: non_identity(ch, ch.to_lowercase().collect()),
+            titlecase: non_identity(ch, to_titlecase(ch)),
+            utf8: ch.encode_utf8(&mut [0; 4]).bytes().map(|b| format!("{:02X}", b)).collect(),
+            utf16: ch.encode_utf16(&mut [0; 2]).iter().map(|u| format!("{:04X}", u)).collect(),
+            age: char_age(codepoint),
+            combining_class: combining_class(codepoint),
+            nfd: codepoint_labels(ch.to_string().nfd()),
+            nfc: codepoint_labels(ch.to_string().nfc()),
+            mirrored: is_mirrored(codepoint),
+            mirror_of: mirror_of(codepoint),
+            numeric_value: numeric_value(codepoint),
+            east_asian_width: east_asian_width(codepoint),
+            line_break: line_break_class(codepoint),
+            noncharacter: is_noncharacter(codepoint),
+            deprecated: is_deprecated(codepoint),
+            whitespace: is_whitespace(codepoint),
+            unicode_whitespace: is_unicode_whitespace(codepoint),
+            jamo: hangul_jamo(codepoint)
+                .map(|jamo| codepoint_labels(jamo.into_iter().filter_map(char::from_u32)))
+                .unwrap_or_default(),
+        }
+    } else {
+        CharInfo {
+            codepoint: hex,
+            character: None,
+            name: "Invalid".to_string(),
+            category: "Invalid".to_string(),
+            block: "Invalid".to_string(),
+            uppercase: None,
+            lowercase: None,
+            titlecase: None,
+            utf8: Vec::new(),
+            utf16: Vec::new(),
+            age: None,
+            combining_class: 0,
+            nfd: Vec::new(),
+            nfc: Vec::new(),
+            mirrored: false,
+            mirror_of: None,
+            numeric_value: None,
+            east_asian_width: String::new(),
+            line_break: line_break_class(codepoint),
+            noncharacter: false,
+            deprecated: false,
+            whitespace: false,
+            unicode_whitespace: false,
+            jamo: Vec::new(),
+        }
+    }
+}
+
+// Full titlecase mapping, which may expand to several characters (e.g. ß -> Ss)
+fn to_titlecase(ch: char) -> String {
+    let mapped: String = unicode_case_mapping::to_titlecase(ch)
+        .iter()
+        .take_while(|&&cp| cp != 0)
+        .filter_map(|&cp| char::from_u32(cp))
+        .collect();
+    if mapped.is_empty() {
+        ch.to_string()
+    } else {
+        mapped
+    }
+}
+
+fn codepoint_labels(chars: impl Iterator<Item = char>) -> Vec<String> {
+    chars.map(|ch| format!("U+{:04X}", ch as u32)).collect()
+}
+
+fn non_identity(ch: char, mapped: String) -> Option<String> {
+    let mut chars = mapped.chars();
+    match (chars.next(), chars.next()) {
+        (Some(only), None) if only == ch => None,
+        _ => Some(mapped),
+    }
+}
+
+// Deprecated: JSON form of `get_char_info`, kept for callers that still parse
+// strings. New code should use the `CharInfo` getters instead.
+#[wasm_bindgen]
+pub fn get_char_info_json(codepoint: u32) -> String {
+    get_char_info(codepoint).to_json()
+}
+
+// JSON info for the first scalar value of `text`, so callers don't have to
+// decode surrogate pairs themselves. Only that one scalar is inspected: for
+// "é" written as e + U+0301 this describes the 'e'. Empty input yields
+// {"error": ...} instead.
+#[wasm_bindgen]
+pub fn get_char_info_for_str(text: &str) -> String {
+    match text.chars().next() {
+        Some(ch) => get_char_info(ch as u32).to_json(),
+        None => json_object(&[("error", json_string("empty string"))]),
+    }
+}
+
+// Split text into extended grapheme clusters (UAX #29), the units a reader
+// sees as one character, as a JSON array of
+//   {"cluster":"e\u0301","codepoints":["U+0065","U+0301"],"names":["LATIN SMALL LETTER E",...]}
+// A flag is two regional indicators and a family emoji several emoji joined
+// by U+200D ZERO WIDTH JOINER, so each comes back as a single cluster.
+#[wasm_bindgen]
+pub fn segment_graphemes(text: &str) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let items: Vec<String> = text
+        .graphemes(true)
+        .map(|cluster| {
+            let names: Vec<String> = cluster
+                .chars()
+                .map(|ch| {
+                    unicode_names2::name(ch)
+                        .map(|n| n.to_string())
+                        .unwrap_or_else(|| "<unnamed>".to_string())
+                })
+                .collect();
+            json_object(&[
+                ("cluster", json_string(cluster)),
+                ("codepoints", json_string_array(&codepoint_labels(cluster.chars()))),
+                ("names", json_string_array(&names)),
+            ])
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+// User names for private use characters, set by `set_pua_labels`
+static PUA_LABELS: Mutex<BTreeMap<u32, String>> = Mutex::new(BTreeMap::new());
+
+// Parse and install labels for `UnicodeExplorer::set_pua_labels`
+fn store_pua_labels(json: &str) -> Result<(), JsValue> {
+    let parsed = js_sys::JSON::parse(json)?;
+    if !parsed.is_object() || js_sys::Array::is_array(&parsed) {
+        return Err(JsValue::from_str("labels must be a JSON object"));
+    }
+
+    let mut labels = BTreeMap::new();
+    for entry in js_sys::Object::entries(&js_sys::Object::from(parsed)).iter() {
+        let entry = js_sys::Array::from(&entry);
+        let key = entry.get(0).as_string().unwrap_or_default();
+        let codepoint = parse_hex_query(&key.trim().to_lowercase())
+            .filter(|&cp| is_private_use(cp))
+            .ok_or_else(|| JsValue::from_str(&format!("\"{}\" is not a private use codepoint", key)))?;
+        let name = entry
+            .get(1)
+            .as_string()
+            .filter(|name| !name.trim().is_empty())
+            .ok_or_else(|| JsValue::from_str(&format!("label for \"{}\" must be a non-empty string", key)))?;
+        labels.insert(codepoint, name);
+    }
+
+    *PUA_LABELS.lock().unwrap_or_else(|e| e.into_inner()) = labels;
+    Ok(())
+}
+
+fn pua_label(codepoint: u32) -> Option<String> {
+    PUA_LABELS.lock().unwrap_or_else(|e| e.into_inner()).get(&codepoint).cloned()
+}
+
+fn is_private_use(codepoint: u32) -> bool {
+    matches!(codepoint, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+// Batched variant of `get_char_info`, returning a JSON array of the same objects
+#[wasm_bindgen]
+pub fn get_char_info_batch(codepoints: &[u32]) -> String {
+    let items: Vec<String> = codepoints.iter().map(|&cp| get_char_info(cp).to_json()).collect();
+    format!("[{}]", items.join(","))
+}
+
+// Reference table for an inclusive range as CSV (RFC 4180, CRLF line
+// endings) with a codepoint,char,name,category,block header. Each row costs a
+// name lookup, so ranges are capped at one plane's worth; export large blocks
+// such as CJK Unified Ideographs Extension B in slices.
+#[wasm_bindgen]
+pub fn export_range_csv(start: u32, end: u32) -> Result<String, JsValue> {
+    if start > end || end > MAX_CODEPOINT {
+        return Err(JsValue::from_str("range must satisfy start <= end <= 0x10FFFF"));
+    }
+    if end - start >= MAX_CSV_ROWS {
+        return Err(JsValue::from_str("range must span at most 65536 codepoints"));
+    }
+
+    let mut out = String::from("codepoint,char,name,category,block\r\n");
+    for codepoint in start..=end {
+        let info = get_char_info(codepoint);
+        // Control characters (tabs, newlines, NUL) would garble most CSV readers
+        let character = info
+            .character
+            .as_deref()
+            .filter(|c| !c.chars().any(char::is_control))
+            .unwrap_or("");
+        let fields = [&info.codepoint, character, &info.name, &info.category, &info.block];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    Ok(out)
+}
+
+// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Non-finite values have no JSON representation
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_opt_string(value: Option<&str>) -> String {
+    value.map(json_string).unwrap_or_else(|| "null".to_string())
+}
+
+fn json_string_array(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| json_string(v)).collect();
+    format!("[{}]", items.join(","))
+}
+
+// Assemble an object from keys and already-encoded JSON values
+fn json_object(fields: &[(&str, String)]) -> String {
+    let body: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), value))
+        .collect();
+    format!("{{{}}}", body.join(","))
+}
+
+// Human name of a plane, e.g. "Supplementary Multilingual Plane"
+#[wasm_bindgen]
+pub fn plane_name(plane: u32) -> String {
+    PLANE_NAMES
+        .get(plane as usize)
+        .copied()
+        .unwrap_or("Invalid")
+        .to_string()
+}
+
+// Plane containing a codepoint, clamped to 0-16 for values past U+10FFFF
+#[wasm_bindgen]
+pub fn plane_of(codepoint: u32) -> u32 {
+    (codepoint / 0x10000).min(16)
+}
+
+// East_Asian_Width abbreviation for a codepoint: "N" (neutral), "Na"
+// (narrow), "A" (ambiguous), "W" (wide), "F" (fullwidth) or "H" (halfwidth).
+// Values past U+10FFFF give "N". Follows `EAST_ASIAN_WIDTH_VERSION`, which is
+// newer than the other `get_char_info` properties; see `data_versions`.
+#[wasm_bindgen]
+pub fn east_asian_width(codepoint: u32) -> String {
+    east_asian_width_of(codepoint).to_string()
+}
+
+fn east_asian_width_of(codepoint: u32) -> &'static str {
+    let index = EAST_ASIAN_WIDTHS.partition_point(|&(_, last, _)| last < codepoint);
+    match EAST_ASIAN_WIDTHS.get(index) {
+        Some(&(first, _, width)) if first <= codepoint => width,
+        _ => "N",
+    }
+}
+
+// Takes two columns in a monospace terminal
+fn is_wide(codepoint: u32) -> bool {
+    matches!(east_asian_width_of(codepoint), "W" | "F")
+}
+
+// Hangul syllable decomposition (Unicode 3.12): U+AC00-D7A3 is laid out
+// arithmetically as leading * 588 + vowel * 28 + trailing. Returns the two
+// or three conjoining jamo, or None outside the syllable range.
+#[wasm_bindgen]
+pub fn hangul_jamo(codepoint: u32) -> Option<Vec<u32>> {
+    const S_BASE: u32 = 0xAC00;
+    const L_BASE: u32 = 0x1100;
+    const V_BASE: u32 = 0x1161;
+    const T_BASE: u32 = 0x11A7;
+    const V_COUNT: u32 = 21;
+    const T_COUNT: u32 = 28;
+    const S_COUNT: u32 = 19 * V_COUNT * T_COUNT;
+
+    let index = codepoint.checked_sub(S_BASE).filter(|&i| i < S_COUNT)?;
+    let mut jamo = vec![
+        L_BASE + index / (V_COUNT * T_COUNT),
+        V_BASE + (index % (V_COUNT * T_COUNT)) / T_COUNT,
+    ];
+    if index % T_COUNT != 0 {
+        jamo.push(T_BASE + index % T_COUNT);
+    }
+    Some(jamo)
+}
+
+// Bidi_Class abbreviation, e.g. "L", "R", "AL", "EN"
+#[wasm_bindgen]
+pub fn bidi_class(codepoint: u32) -> String {
+    use unic_char_property::EnumeratedCharProperty;
+    use unic_ucd_bidi::BidiClass;
+
+    char::from_u32(codepoint)
+        .map(|ch| BidiClass::of(ch).abbr_name().to_string())
+        .unwrap_or_else(|| "Invalid".to_string())
+}
+
+// Unicode version a character was introduced in, as "major.minor" (e.g. "6.0").
+// None for unassigned codepoints and for anything newer than the UCD tables.
+#[wasm_bindgen]
+pub fn char_age(codepoint: u32) -> Option<String> {
+    use unic_ucd_age::Age;
+
+    let version = Age::of(char::from_u32(codepoint)?)?.actual();
+    Some(format!("{}.{}", version.major, version.minor))
+}
+
+// Canonical_Combining_Class, which drives mark reordering in NFC/NFD
+// (e.g. 230 for U+0301 COMBINING ACUTE ACCENT). 0 for starters and anything
+// without a class.
+#[wasm_bindgen]
+pub fn combining_class(codepoint: u32) -> u8 {
+    use unic_ucd_normal::CanonicalCombiningClass;
+
+    char::from_u32(codepoint)
+        .map(|ch| CanonicalCombiningClass::of(ch).number())
+        .unwrap_or(0)
+}
+
+// Numeric value as a string, e.g. "7" for U+0667 ARABIC-INDIC DIGIT SEVEN,
+// "0.5" for '½' or "1/3" where the decimal wouldn't terminate. Decimal digits
+// of every script are covered; other numbers only as far as `NUMERIC_VALUES`
+// goes, so rarer ones (e.g. Aegean numbers) return None.
+#[wasm_bindgen]
+pub fn numeric_value(codepoint: u32) -> Option<String> {
+    use unic_ucd_category::GeneralCategory;
+
+    let is_digit = |cp: u32| {
+        char::from_u32(cp).map(GeneralCategory::of) == Some(GeneralCategory::DecimalNumber)
+    };
+
+    // Nd digits come in contiguous runs of ten starting at zero
+    if is_digit(codepoint) {
+        let mut first = codepoint;
+        while first > 0 && is_digit(first - 1) {
+            first -= 1;
+        }
+        return Some(((codepoint - first) % 10).to_string());
+    }
+
+    NUMERIC_VALUES
+        .iter()
+        .find(|&&(first, last, _, _)| (first..=last).contains(&codepoint))
+        .map(|&(first, _, numerator, denominator)| {
+            format_ratio(numerator + (codepoint - first), denominator)
+        })
+}
+
+// Decimal when exact (only 2 and 5 in the denominator), otherwise "n/d"
+fn format_ratio(numerator: u32, denominator: u32) -> String {
+    if numerator.is_multiple_of(denominator) {
+        return (numerator / denominator).to_string();
+    }
+    let mut rest = denominator;
+    for factor in [2, 5] {
+        while rest.is_multiple_of(factor) {
+            rest /= factor;
+        }
+    }
+    if rest == 1 {
+        (numerator as f64 / denominator as f64).to_string()
+    } else {
+        format!("{}/{}", numerator, denominator)
+    }
+}
+
+// Source-code escape for a character. `lang` is one of "rust" (\u{1F600}),
+// "js" or "javascript" (\uD83D\uDE00), "python" (\U0001F600) or "c"
+// (\U0001F600); anything else falls back to Rust. Python and C use the short
+// \uXXXX form inside the BMP. Surrogates and out-of-range values give "".
+#[wasm_bindgen]
+pub fn escape_for(codepoint: u32, lang: &str) -> String {
+    let Some(ch) = char::from_u32(codepoint) else {
+        return String::new();
+    };
+    match lang.to_ascii_lowercase().as_str() {
+        "js" | "javascript" => ch
+            .encode_utf16(&mut [0; 2])
+            .iter()
+            .map(|unit| format!("\\u{:04X}", unit))
+            .collect(),
+        "python" | "c" if codepoint > 0xFFFF => format!("\\U{:08X}", codepoint),
+        "python" | "c" => format!("\\u{:04X}", codepoint),
+        _ => format!("\\u{{{:04X}}}", codepoint),
+    }
+}
+
+// Bit-level walkthrough of how a scalar value is encoded, as JSON:
+//   {"codepoint":"U+00E9","scalar":233,"binary":"1110 1001",
+//    "utf8":{"pattern":"110xxxxx 10xxxxxx","payload":"00011 101001",
+//            "bytes":[{"hex":"C3","binary":"11000011","marker":"110","payload":"00011"},...]},
+//    "utf16":{"units":[{"hex":"00E9","binary":"0000000011101001"}],"surrogates":null}}
+// For supplementary characters "surrogates" holds the 20-bit offset from
+// U+10000 and how its halves land in the high and low surrogate. Surrogates
+// and values past U+10FFFF are not scalar values and are rejected.
+#[wasm_bindgen]
+pub fn explain_encoding(codepoint: u32) -> Result<String, JsValue> {
+    let ch = char::from_u32(codepoint)
+        .ok_or_else(|| JsValue::from_str("codepoint must be a Unicode scalar value"))?;
+    let bits = |value: u32, width: usize| format!("{:0width$b}", value, width = width);
+
+    // Payload bits per byte: 7; 5+6; 4+6+6; 3+6+6+6
+    let len = ch.len_utf8();
+    let lead_bits = if len == 1 { 7 } else { 7 - len };
+    let total_bits = lead_bits + 6 * (len - 1);
+    let scalar_bits = bits(codepoint, total_bits);
+    let mut groups = vec![&scalar_bits[..lead_bits]];
+    groups.extend((0..len - 1).map(|i| &scalar_bits[lead_bits + 6 * i..lead_bits + 6 * (i + 1)]));
+
+    let bytes: Vec<String> = ch
+        .encode_utf8(&mut [0; 4])
+        .bytes()
+        .enumerate()
+        .map(|(i, byte)| {
+            let binary = bits(byte as u32, 8);
+            let marker_len = 8 - groups[i].len();
+            json_object(&[
+                ("hex", json_string(&format!("{:02X}", byte))),
+                ("binary", json_string(&binary)),
+                ("marker", json_string(&binary[..marker_len])),
+                ("payload", json_string(&binary[marker_len..])),
+            ])
+        })
+        .collect();
+    let pattern: Vec<String> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let marker = match (i, len) {
+                (_, 1) => "0".to_string(),
+                (0, _) => format!("{}0", "1".repeat(len)),
+                _ => "10".to_string(),
+            };
+            format!("{}{}", marker, "x".repeat(group.len()))
+        })
+        .collect();
+
+    let mut units = [0u16; 2];
+    let units = ch.encode_utf16(&mut units);
+    let unit_json: Vec<String> = units
+        .iter()
+        .map(|&unit| {
+            json_object(&[
+                ("hex", json_string(&format!("{:04X}", unit))),
+                ("binary", json_string(&bits(unit as u32, 16))),
+            ])
+        })
+        .collect();
+    let surrogates = if units.len() == 2 {
+        // 110110 + top ten offset bits, then 110111 + bottom ten
+        let offset = codepoint - 0x10000;
+        let offset_bits = bits(offset, 20);
+        json_object(&[
+            ("offset", json_string(&format!("0x{:05X}", offset))),
+            ("offset_binary", json_string(&format!("{} {}", &offset_bits[..10], &offset_bits[10..]))),
+            ("high", json_string(&format!("110110 {}", &offset_bits[..10]))),
+            ("low", json_string(&format!("110111 {}", &offset_bits[10..]))),
+        ])
+    } else {
+        "null".to_string()
+    };
+
+    // Nibble groups counted from the least significant bit
+    let plain = format!("{:b}", codepoint);
+    let padded = format!("{:0>width$}", plain, width = plain.len().div_ceil(4) * 4);
+    let nibbles: Vec<&str> = (0..padded.len() / 4).map(|i| &padded[i * 4..i * 4 + 4]).collect();
+    let binary = nibbles.join(" ");
+
+    Ok(json_object(&[
+        ("codepoint", json_string(&format!("U+{:04X}", codepoint))),
+        ("scalar", codepoint.to_string()),
+        ("binary", json_string(&binary)),
+        (
+            "utf8",
+            json_object(&[
+                ("pattern", json_string(&pattern.join(" "))),
+                ("payload", json_string(&groups.join(" "))),
+                ("bytes", format!("[{}]", bytes.join(","))),
+            ]),
+        ),
+        (
+            "utf16",
+            json_object(&[
+                ("units", format!("[{}]", unit_json.join(","))),
+                ("surrogates", surrogates),
+            ]),
+        ),
+    ]))
+}
+
+// UTF-8 bytes of `text` exactly as given; no byte order mark is added
+// (a leading U+FEFF in the input is kept as EF BB BF)
+#[wasm_bindgen]
+pub fn encode_utf8_bytes(text: &str) -> Vec<u8> {
+    text.as_bytes().to_vec()
+}
+
+// UTF-16 code units of `text`, with supplementary characters as surrogate
+// pairs. No byte order mark is added and units carry no endianness.
+#[wasm_bindgen]
+pub fn encode_utf16_units(text: &str) -> Vec<u16> {
+    text.encode_utf16().collect()
+}
+
+// Decode raw bytes as UTF-8 into a JSON array with one entry per scalar or
+// error, each tagged with its byte offset and length:
+//   {"offset":0,"length":1,"codepoint":65}
+//   {"offset":1,"length":2,"error":"overlong encoding"}
+// Nothing is replaced with U+FFFD. A truncated sequence covers only the
+// bytes before the one that interrupted it, so decoding resumes there.
+#[wasm_bindgen]
+pub fn decode_utf8(bytes: &[u8]) -> String {
+    let mut items = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let (length, result) = decode_utf8_at(&bytes[offset..]);
+        let value = match result {
+            Ok(codepoint) => ("codepoint", codepoint.to_string()),
+            Err(error) => ("error", json_string(error)),
+        };
+        items.push(json_object(&[
+            ("offset", offset.to_string()),
+            ("length", length.to_string()),
+            value,
+        ]));
+        offset += length;
+    }
+    format!("[{}]", items.join(","))
+}
+
+// Decode the sequence at the start of `bytes`; returns the bytes it covers
+// and the scalar value or why it is invalid
+fn decode_utf8_at(bytes: &[u8]) -> (usize, Result<u32, &'static str>) {
+    let lead = bytes[0];
+    let (length, min, initial) = match lead {
+        0x00..=0x7F => return (1, Ok(lead as u32)),
+        0x80..=0xBF => return (1, Err("unexpected continuation byte")),
+        0xC0..=0xDF => (2, 0x80, (lead & 0x1F) as u32),
+        0xE0..=0xEF => (3, 0x800, (lead & 0x0F) as u32),
+        0xF0..=0xF7 => (4, 0x10000, (lead & 0x07) as u32),
+        _ => return (1, Err("invalid byte")),
+    };
+
+    let mut codepoint = initial;
+    for i in 1..length {
+        match bytes.get(i) {
+            Some(&b) if b & 0xC0 == 0x80 => codepoint = (codepoint << 6) | (b & 0x3F) as u32,
+            _ => return (i, Err("truncated sequence")),
+        }
+    }
+
+    let result = if codepoint < min {
+        Err("overlong encoding")
+    } else if (0xD800..=0xDFFF).contains(&codepoint) {
+        Err("encoded surrogate")
+    } else if codepoint > MAX_CODEPOINT {
+        Err("out of range")
+    } else {
+        Ok(codepoint)
+    };
+    (length, result)
+}
+
+// VS1-VS16 (U+FE00-FE0F) and the supplement VS17-VS256 (U+E0100-E01EF)
+#[wasm_bindgen]
+pub fn is_variation_selector(codepoint: u32) -> bool {
+    matches!(codepoint, 0xFE00..=0xFE0F | 0xE0100..=0xE01EF)
+}
+
+// "VS16" style label for a variation selector
+fn variation_selector_name(codepoint: u32) -> String {
+    match codepoint {
+        0xFE00..=0xFE0F => format!("VS{}", codepoint - 0xFE00 + 1),
+        _ => format!("VS{}", codepoint - 0xE0100 + 17),
+    }
+}
+
+// UAX #14 Line_Break class as its two- or three-letter code: "AL"
+// (alphabetic), "BA" (break after), "GL" (non-breaking glue), "SP", "ZWJ"...
+// Surrogates are "SG"; unassigned codepoints and values past U+10FFFF "XX".
+#[wasm_bindgen]
+pub fn line_break_class(codepoint: u32) -> String {
+    use unicode_linebreak::BreakClass::*;
+
+    if codepoint > MAX_CODEPOINT {
+        return "XX".to_string();
+    }
+    let code = match unicode_linebreak::break_property(codepoint) {
+        Mandatory => "BK",
+        CarriageReturn => "CR",
+        LineFeed => "LF",
+        CombiningMark => "CM",
+        NextLine => "NL",
+        Surrogate => "SG",
+        WordJoiner => "WJ",
+        ZeroWidthSpace => "ZW",
+        NonBreakingGlue => "GL",
+        Space => "SP",
+        ZeroWidthJoiner => "ZWJ",
+        BeforeAndAfter => "B2",
+        After => "BA",
+        Before => "BB",
+        Hyphen => "HY",
+        Contingent => "CB",
+        ClosePunctuation => "CL",
+        CloseParenthesis => "CP",
+        Exclamation => "EX",
+        Inseparable => "IN",
+        NonStarter => "NS",
+        OpenPunctuation => "OP",
+        Quotation => "QU",
+        InfixSeparator => "IS",
+        Numeric => "NU",
+        Postfix => "PO",
+        Prefix => "PR",
+        Symbol => "SY",
+        Ambiguous => "AI",
+        Alphabetic => "AL",
+        ConditionalJapaneseStarter => "CJ",
+        EmojiBase => "EB",
+        EmojiModifier => "EM",
+        HangulLvSyllable => "H2",
+        HangulLvtSyllable => "H3",
+        HebrewLetter => "HL",
+        Ideographic => "ID",
+        HangulLJamo => "JL",
+        HangulVJamo => "JV",
+        HangulTJamo => "JT",
+        RegionalIndicator => "RI",
+        ComplexContext => "SA",
+        Unknown => "XX",
+    };
+    code.to_string()
+}
+
+// The 66 noncharacters: U+FDD0-FDEF plus the last two codepoints of every
+// plane (U+FFFE, U+FFFF, U+1FFFE, ... U+10FFFF). Permanently reserved for
+// internal use, never assigned
+#[wasm_bindgen]
+pub fn is_noncharacter(codepoint: u32) -> bool {
+    codepoint <= MAX_CODEPOINT && ((0xFDD0..=0xFDEF).contains(&codepoint) || codepoint & 0xFFFE == 0xFFFE)
+}
+
+// Deprecated property (PropList.txt): characters whose use is strongly
+// discouraged, such as U+0149 and the U+206A-206F format controls. The list
+// is short and stable, so it is written out here.
+#[wasm_bindgen]
+pub fn is_deprecated(codepoint: u32) -> bool {
+    matches!(
+        codepoint,
+        0x0149 | 0x0673 | 0x0F77 | 0x0F79 | 0x17A3..=0x17A4 | 0x206A..=0x206F | 0x2329..=0x232A | 0xE0001
+    )
+}
+
+// Rust's `char::is_whitespace`: the White_Space property as of the Unicode
+// version the standard library was built with
+#[wasm_bindgen]
+pub fn is_whitespace(codepoint: u32) -> bool {
+    char::from_u32(codepoint).is_some_and(char::is_whitespace)
+}
+
+// White_Space from the UCD tables shipped here (see `unicode_version`). Same
+// definition as `is_whitespace`, so the two only disagree where the data
+// versions do. Neither includes look-alikes such as U+200B ZERO WIDTH SPACE
+// or U+FEFF, which are format characters (Cf), not whitespace.
+#[wasm_bindgen]
+pub fn is_unicode_whitespace(codepoint: u32) -> bool {
+    char::from_u32(codepoint).is_some_and(unic_ucd_common::is_white_space)
+}
+
+// Bidi_Mirrored: drawn mirrored in right-to-left text, like '(' or '∈'
+#[wasm_bindgen]
+pub fn is_mirrored(codepoint: u32) -> bool {
+    char::from_u32(codepoint)
+        .map(unicode_bidi_mirroring::is_mirroring)
+        .unwrap_or(false)
+}
+
+// Bidi_Mirroring_Glyph, e.g. '(' -> ')'. Some mirrored characters such as
+// '∛' have no mirror glyph and return None.
+#[wasm_bindgen]
+pub fn mirror_of(codepoint: u32) -> Option<u32> {
+    char::from_u32(codepoint)
+        .and_then(unicode_bidi_mirroring::get_mirrored)
+        .map(|ch| ch as u32)
+}
+
+// Strong right-to-left characters (Bidi_Class R or AL)
+// Text drawn for a cell's glyph: combining marks sit on `mark_base`, and
+// right-to-left glyphs are wrapped in FSI/PDI so each cell lays out
+// independently of its neighbours
+fn glyph_text(ch: char, mark_base: char) -> String {
+    use unic_ucd_category::GeneralCategory;
+
+    let text = match GeneralCategory::of(ch) {
+        GeneralCategory::NonspacingMark
+        | GeneralCategory::SpacingMark
+        | GeneralCategory::EnclosingMark => format!("{}{}", mark_base, ch),
+        _ => ch.to_string(),
+    };
+
+    if is_rtl(ch) {
+        format!("\u{2068}{}\u{2069}", text)
+    } else {
+        text
+    }
+}
+
+fn is_rtl(ch: char) -> bool {
+    use unic_ucd_bidi::BidiClass;
+
+    matches!(BidiClass::of(ch), BidiClass::RightToLeft | BidiClass::ArabicLetter)
+}
+
+// Unicode version of the core UCD tables (categories, blocks, bidi classes),
+// formatted like "10.0.0"
+#[wasm_bindgen]
+pub fn unicode_version() -> String {
+    let v = unic_ucd_category::UNICODE_VERSION;
+    format!("{}.{}.{}", v.major, v.minor, v.micro)
+}
+
+// Per-dataset Unicode versions as JSON, since some properties come from
+// crates that track newer Unicode releases than the core tables
+#[wasm_bindgen]
+pub fn data_versions() -> String {
+    let triple = |(major, minor, micro): (u64, u64, u64)| format!("{}.{}.{}", major, minor, micro);
+    let emoji = unic_emoji_char::EMOJI_VERSION;
+
+    json_object(&[
+        ("ucd", json_string(&unicode_version())),
+        ("emoji", json_string(&format!("{}.{}.{}", emoji.major, emoji.minor, emoji.micro))),
+        ("script", json_string(&triple(unicode_script::UNICODE_VERSION))),
+        ("case_mapping", json_string(&triple(unicode_case_mapping::UNICODE_VERSION))),
+        ("confusables", json_string(&triple(unicode_security::UNICODE_VERSION))),
+        ("east_asian_width", json_string(&triple(EAST_ASIAN_WIDTH_VERSION))),
+        ("line_break", {
+            let (major, minor, micro) = unicode_linebreak::UNICODE_VERSION;
+            json_string(&triple((major.into(), minor.into(), micro.into())))
+        }),
+        ("mirroring", {
+            let (major, minor, micro) = unicode_bidi_mirroring::UNICODE_VERSION;
+            json_string(&triple((major.into(), minor.into(), micro.into())))
+        }),
+        ("normalization", {
+            let (major, minor, micro) = unicode_normalization::UNICODE_VERSION;
+            json_string(&triple((major.into(), minor.into(), micro.into())))
+        }),
+    ])
+}
+
+// UTS #39 confusables skeleton. Two strings that look alike, such as Latin 'a'
+// and Cyrillic 'а', share a skeleton; invalid codepoints give an empty string.
+#[wasm_bindgen]
+pub fn confusable_skeleton(codepoint: u32) -> String {
+    char::from_u32(codepoint)
+        .map(|ch| unicode_security::skeleton(&ch.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// Emoji property. Characters that only form an emoji in sequence, such as
+// regional indicators (flags) and keycap bases like '#' and '0'-'9', are
+// reported as emoji too: they are base emoji in the UCD data.
+#[wasm_bindgen]
+pub fn is_emoji(codepoint: u32) -> bool {
+    char::from_u32(codepoint)
+        .map(unic_emoji_char::is_emoji)
+        .unwrap_or(false)
+}
+
+// Emoji_Presentation property: renders as colorful emoji by default, without
+// needing a VS16 variation selector
+#[wasm_bindgen]
+pub fn has_emoji_presentation(codepoint: u32) -> bool {
+    char::from_u32(codepoint)
+        .map(unic_emoji_char::is_emoji_presentation)
+        .unwrap_or(false)
+}
+
+// Name search ranked by `name_relevance`, then by name length and codepoint.
+// A hex query like "U+1F600" returns that codepoint. Ranking needs every
+// match, so `limit` only truncates the sorted list and never ends the scan
+// early; the cost is the same for any limit. `max_scan` caps how
+// many codepoints (counting up from U+0000) are examined, bounding the time of
+// one synchronous call; the ranking then only covers that prefix, so rare
+// names or ones in higher planes can be missed. Omit it to scan everything,
+// or use `SearchSession` to spread a full scan across frames.
+#[wasm_bindgen]
+pub fn search_characters(query: &str, limit: u32, max_scan: Option<u32>) -> Vec<u32> {
+    let query_lower = query.trim().to_lowercase();
+    if let Some(cp) = parse_hex_query(&query_lower) {
+        return vec![cp];
+    }
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let end = match max_scan {
+        Some(0) => return Vec::new(),
+        Some(n) => (n - 1).min(MAX_CODEPOINT),
+        None => MAX_CODEPOINT,
+    };
+
+    let mut scored: Vec<(u32, usize, u32)> = (0..=end)
+        .filter_map(|cp| ranked_match(cp, &query_lower))
+        .collect();
+
+    scored.sort_unstable();
+    scored.into_iter().take(limit as usize).map(|(_, _, cp)| cp).collect()
+}
+
+// Sort key for a name match: (relevance, name length, codepoint)
+fn ranked_match(codepoint: u32, query_lower: &str) -> Option<(u32, usize, u32)> {
+    let name = char::from_u32(codepoint).and_then(unicode_names2::name)?;
+    let name_lower = name.to_string().to_lowercase();
+    name_relevance(&name_lower, query_lower).map(|score| (score, name_lower.len(), codepoint))
+}
+
+// Lower is better: exact name, then whole-word matches (leading, as a phrase,
+// in any order), then plain substrings. Every query token must appear in the
+// name; None means no match.
+fn name_relevance(name_lower: &str, query_lower: &str) -> Option<u32> {
+    let tokens: Vec<&str> = query_lower.split_whitespace().collect();
+    if tokens.is_empty() || !tokens.iter().all(|token| name_lower.contains(token)) {
+        return None;
+    }
+    if name_lower == query_lower {
+        return Some(0);
+    }
+
+    let words: Vec<&str> = name_lower.split([' ', '-']).collect();
+    let whole_words = tokens.iter().all(|token| words.contains(token));
+    let phrase = name_lower.contains(query_lower);
+    let leading = name_lower.starts_with(query_lower);
+    Some(match (whole_words, phrase, leading) {
+        (true, _, true) => 1,
+        (true, true, false) => 2,
+        (true, false, _) => 3,
+        (false, _, true) => 4,
+        (false, true, false) => 5,
+        (false, false, _) => 6,
+    })
+}
+
+// Name search starting at `start` instead of 0, for "load more" pagination.
+// Pass the previous page's largest result + 1 as the next `start`: the scan
+// stops on the match that fills the page, so nothing between that result and
+// the end of the scan is skipped, and a short page means the range is
+// exhausted. The hex fast path only applies to the first page (start == 0).
+#[wasm_bindgen]
+pub fn search_from(query: &str, start: u32, limit: u32) -> Vec<u32> {
+    let mut session = SearchSession::new(query, limit);
+    if start > 0 {
+        session.started = true;
+        session.next_codepoint = start;
+    }
+    while !session.step(MAX_CODEPOINT + 1) {}
+    session.results
+}
+
+// Incremental search that scans a bounded number of codepoints per step,
+// so JS can spread a broad query across animation frames. `new` collects
+// plain substring matches in codepoint order and stops at `limit`; `ranked`
+// orders matches like `search_characters`, so it always scans the full range.
+#[wasm_bindgen]
+pub struct SearchSession {
+    query_lower: String,
+    limit: usize,
+    next_codepoint: u32,
+    started: bool,
+    done: bool,
+    results: Vec<u32>,
+    // Collected matches while ranking; None for codepoint-order sessions
+    ranked: Option<Vec<(u32, usize, u32)>>,
+}
+
+#[wasm_bindgen]
+impl SearchSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new(query: &str, limit: u32) -> SearchSession {
+        SearchSession {
+            query_lower: query.trim().to_lowercase(),
+            limit: limit as usize,
+            next_codepoint: 0,
+            started: false,
+            done: false,
+            results: Vec::new(),
+            ranked: None,
+        }
+    }
+
+    // Relevance-ranked session; results are available once it is done
+    pub fn ranked(query: &str, limit: u32) -> SearchSession {
+        SearchSession {
+            ranked: Some(Vec::new()),
+            ..SearchSession::new(query, limit)
+        }
+    }
+
+    // Scan up to `max_codepoints` more codepoints; returns true once complete
+    pub fn step(&mut self, max_codepoints: u32) -> bool {
+        if self.done {
+            return true;
+        }
+
+        // Check if it's a hex codepoint search
+        if !self.started {
+            self.started = true;
+            if let Some(cp) = parse_hex_query(&self.query_lower) {
+                self.results.push(cp);
+                self.done = true;
+                return true;
+            }
+        }
+
+        // Search by name
+        let end = self.next_codepoint.saturating_add(max_codepoints).min(MAX_CODEPOINT + 1);
+        if let Some(scored) = &mut self.ranked {
+            if self.limit > 0 {
+                scored.extend((self.next_codepoint..end).filter_map(|cp| ranked_match(cp, &self.query_lower)));
+            }
+            self.next_codepoint = end;
+            if end > MAX_CODEPOINT {
+                scored.sort_unstable();
+                self.results = scored.iter().take(self.limit).map(|&(_, _, cp)| cp).collect();
+                self.done = true;
+            }
+            return self.done;
+        }
+
+        while self.next_codepoint < end {
+            if self.results.len() >= self.limit {
+                self.done = true;
+                return true;
+            }
+
+            let cp = self.next_codepoint;
+            self.next_codepoint += 1;
+            if name_matches(cp, &self.query_lower) {
+                self.results.push(cp);
+            }
+        }
+
+        self.done = self.results.len() >= self.limit || self.next_codepoint > MAX_CODEPOINT;
+        self.done
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    // Next codepoint to be scanned, useful for progress reporting
+    pub fn progress(&self) -> u32 {
+        self.next_codepoint
+    }
+
+    pub fn results(&self) -> Vec<u32> {
+        self.results.clone()
+    }
+}
+
+// Typo-tolerant name search. Every whitespace-separated query token must be
+// within `max_distance` edits of some name token; results are ordered by the
+// summed edit distance, then by codepoint. Scans the full range, so prefer
+// `search_characters` when an exact substring is enough.
+#[wasm_bindgen]
+pub fn search_characters_fuzzy(query: &str, limit: u32, max_distance: u32) -> Vec<u32> {
+    let query_lower = query.trim().to_lowercase();
+    if let Some(cp) = parse_hex_query(&query_lower) {
+        return vec![cp];
+    }
+
+    let query_tokens: Vec<Vec<char>> = query_lower
+        .split_whitespace()
+        .map(|token| token.chars().collect())
+        .collect();
+    if query_tokens.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(u32, u32)> = Vec::new();
+    for cp in 0..=MAX_CODEPOINT {
+        let Some(name) = char::from_u32(cp).and_then(unicode_names2::name) else {
+            continue;
+        };
+        let name_lower = name.to_string().to_lowercase();
+        let name_tokens: Vec<Vec<char>> = name_lower
+            .split_whitespace()
+            .map(|token| token.chars().collect())
+            .collect();
+
+        let mut total = 0;
+        let all_matched = query_tokens.iter().all(|query_token| {
+            let best = name_tokens
+                .iter()
+                .filter_map(|name_token| bounded_levenshtein(query_token, name_token, max_distance))
+                .min();
+            if let Some(distance) = best {
+                total += distance;
+            }
+            best.is_some()
+        });
+
+        if all_matched {
+            scored.push((total, cp));
+        }
+    }
+
+    scored.sort_unstable();
+    scored.into_iter().take(limit as usize).map(|(_, cp)| cp).collect()
+}
+
+// Levenshtein distance, or None as soon as it must exceed `max`
+fn bounded_levenshtein(a: &[char], b: &[char], max: u32) -> Option<u32> {
+    let max = max as usize;
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance as u32)
+}
+
+// First codepoint of the block whose name matches (case-insensitive substring).
+// An exact name match wins over earlier partial matches.
+#[wasm_bindgen]
+pub fn find_block(name: &str) -> Option<u32> {
+    find_block_range(name).map(|(low, _)| low)
+}
+
+// Inclusive codepoint range of the block `find_block` would pick
+fn find_block_range(name: &str) -> Option<(u32, u32)> {
+    use unic_ucd_block::BlockIter;
+
+    let query = name.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    BlockIter::new()
+        .find(|block| block.name.to_lowercase() == query)
+        .or_else(|| BlockIter::new().find(|block| block.name.to_lowercase().contains(&query)))
+        .map(|block| (block.range.low as u32, block.range.high as u32))
+}
+
+// Codepoints of the block `find_block` would pick, optionally only assigned
+// ones; empty if nothing matches. Blocks can be large: CJK Unified
+// Ideographs Extension B alone is over 42,000 codepoints.
+#[wasm_bindgen]
+pub fn block_characters(name: &str, assigned_only: bool) -> Vec<u32> {
+    let Some((low, high)) = find_block_range(name) else {
+        return Vec::new();
+    };
+    (low..=high)
+        .filter(|&cp| !assigned_only || is_assigned(cp))
+        .collect()
+}
+
+// All blocks as a JSON array of {name, start, end}, sorted by start codepoint
+#[wasm_bindgen]
+pub fn list_blocks() -> String {
+    use unic_ucd_block::BlockIter;
+
+    let mut blocks: Vec<_> = BlockIter::new().collect();
+    blocks.sort_by_key(|block| block.range.low);
+
+    let items: Vec<String> = blocks
+        .iter()
+        .map(|block| {
+            json_object(&[
+                ("name", json_string(block.name)),
+                ("start", (block.range.low as u32).to_string()),
+                ("end", (block.range.high as u32).to_string()),
+            ])
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+// All scripts as a JSON array of {name, tag, first, count, shared}, sorted by
+// name: `first` is the lowest codepoint in the script and `count` how many it
+// has. Common and Inherited are flagged `shared`, since their characters are
+// scattered across many blocks; Unknown (unassigned) is left out. The full
+// scan runs once and the result is cached.
+#[wasm_bindgen]
+pub fn list_scripts() -> String {
+    use unicode_script::{Script, UnicodeScript};
+
+    static SCRIPTS: OnceLock<String> = OnceLock::new();
+    SCRIPTS
+        .get_or_init(|| {
+            let mut stats: HashMap<Script, (u32, u32)> = HashMap::new();
+            for ch in (0..=MAX_CODEPOINT).filter_map(char::from_u32) {
+                let script = ch.script();
+                if script != Script::Unknown {
+                    stats.entry(script).or_insert((ch as u32, 0)).1 += 1;
+                }
+            }
+
+            let mut scripts: Vec<_> = stats.into_iter().collect();
+            scripts.sort_by_key(|(script, _)| script.full_name());
+            let items: Vec<String> = scripts
+                .iter()
+                .map(|&(script, (first, count))| {
+                    json_object(&[
+                        ("name", json_string(script.full_name())),
+                        ("tag", json_string(script.short_name())),
+                        ("first", first.to_string()),
+                        ("count", count.to_string()),
+                        ("shared", matches!(script, Script::Common | Script::Inherited).to_string()),
+                    ])
+                })
+                .collect();
+            format!("[{}]", items.join(","))
+        })
+        .clone()
+}
+
+fn parse_hex_query(query_lower: &str) -> Option<u32> {
+    if query_lower.starts_with("u+") || query_lower.starts_with("0x") {
+        let hex_str = query_lower.trim_start_matches("u+").trim_start_matches("0x");
+        if let Ok(cp) = u32::from_str_radix(hex_str, 16) {
+            if char::from_u32(cp).is_some() {
+                return Some(cp);
+            }
+        }
+    }
+    None
+}
+
+fn name_matches(codepoint: u32, query_lower: &str) -> bool {
+    char::from_u32(codepoint)
+        .and_then(unicode_names2::name)
+        .is_some_and(|name| name.to_string().to_lowercase().contains(query_lower))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1000x800 CSS pixel view at zoom 1 with the grid's top-left at the origin
+    fn view() -> ViewState {
+        ViewState::new(1000.0, 800.0)
+    }
+
+    #[test]
+    fn codepoint_at_maps_cells() {
+        let view = view();
+        assert_eq!(view.codepoint_at(5.0, 5.0), Some(0x0000));
+        assert_eq!(view.codepoint_at(25.0, 5.0), Some(0x0001));
+        assert_eq!(view.codepoint_at(5.0, 25.0), Some(0x0100));
+        assert_eq!(view.codepoint_at(-1.0, 5.0), None);
+    }
+
+    #[test]
+    fn codepoint_at_cell_boundaries() {
+        let view = view();
+        // Exact top-left corner of the grid and of the next cell belong to that cell
+        assert_eq!(view.codepoint_at(0.0, 0.0), Some(0x0000));
+        assert_eq!(view.codepoint_at(20.0, 0.0), Some(0x0001));
+        assert_eq!(view.codepoint_at(19.999, 19.999), Some(0x0000));
+        assert_eq!(view.codepoint_at(20.0, 20.0), Some(0x0101));
+        // Last cell and just past the far edges
+        assert_eq!(view.codepoint_at(5119.9, 5119.9), Some(0xFFFF));
+        assert_eq!(view.codepoint_at(5120.0, 5.0), None);
+        assert_eq!(view.codepoint_at(5.0, 5120.0), None);
+        assert_eq!(view.codepoint_at(-0.001, 0.0), None);
+    }
+
+    #[test]
+    fn codepoint_at_fractional_zoom() {
+        let mut view = view();
+        view.zoom = 0.25; // 5px cells
+        assert_eq!(view.codepoint_at(4.999, 0.0), Some(0x0000));
+        assert_eq!(view.codepoint_at(5.0, 0.0), Some(0x0001));
+        assert_eq!(view.codepoint_at(12.5, 7.5), Some(0x0102));
+
+        view.zoom = 1.5; // 30px cells
+        view.offset_x = 7.5;
+        view.offset_y = -15.0;
+        assert_eq!(view.codepoint_at(7.5, 0.0), Some(0x0000));
+        assert_eq!(view.codepoint_at(7.4, 0.0), None);
+        assert_eq!(view.codepoint_at(37.5, 15.0), Some(0x0101));
+    }
+
+    #[test]
+    fn codepoint_at_huge_pans() {
+        let mut view = view();
+        for offset in [1e12, -1e12, f64::MAX, -f64::MAX, 4.0e9, -4.0e9] {
+            view.offset_x = offset;
+            view.offset_y = 0.0;
+            assert_eq!(view.codepoint_at(500.0, 400.0), None, "offset_x {offset}");
+            view.offset_x = 0.0;
+            view.offset_y = offset;
+            assert_eq!(view.codepoint_at(500.0, 400.0), None, "offset_y {offset}");
+        }
+    }
+
+    #[test]
+    fn cell_of_plane_edges() {
+        let view = view();
+        assert_eq!(view.cell_of(0xFFFF), (255, 255));
+        assert_eq!(view.cell_of(0x10000), (0, 0));
+        assert_eq!(view.cell_of(0x10FFFF), (255, 255));
+    }
+
+    #[test]
+    fn grid_position_only_in_displayed_plane() {
+        let mut view = view();
+        assert_eq!(view.grid_position(0xFFFF), Some((255, 255)));
+        assert_eq!(view.grid_position(0x10000), None);
+        assert_eq!(view.grid_position(0x10FFFF), None);
+        assert_eq!(view.grid_position(0x110000), None);
+
+        view.plane = 1;
+        assert_eq!(view.grid_position(0xFFFF), None);
+        assert_eq!(view.grid_position(0x10000), Some((0, 0)));
+        assert_eq!(view.grid_position(0x1F600), Some((0xF6, 0x00)));
+
+        view.plane = 16;
+        assert_eq!(view.grid_position(0x10FFFF), Some((255, 255)));
+        assert_eq!(view.cell_codepoint(255, 255), 0x10FFFF);
+        assert_eq!(view.grid_position(0x110000), None);
+    }
+
+    #[test]
+    fn cell_mapping_with_other_grid_widths() {
+        let mut view = view();
+        for cols in [128, 512, 1024] {
+            view.grid_cols = cols;
+            view.plane = 1;
+            let rows = 0x10000 / cols;
+            assert_eq!(view.grid_rows(), rows);
+            assert_eq!(view.cell_of(0x1FFFF), (rows - 1, cols - 1));
+            assert_eq!(view.grid_position(0x10000 + cols), Some((1, 0)));
+            for codepoint in [0x10000, 0x10000 + cols - 1, 0x1ABCD, 0x1FFFF] {
+                let (row, col) = view.cell_of(codepoint);
+                assert_eq!(view.cell_codepoint(row, col), codepoint, "{cols} columns");
+            }
+            // Hit-testing agrees with the layout: the last cell of row 0
+            let x = (cols as f64 - 0.5) * CELL_SIZE;
+            assert_eq!(view.codepoint_at(x, 1.0), Some(0x10000 + cols - 1));
+        }
+    }
+
+    #[test]
+    fn hit_testing_ignores_device_pixel_ratio() {
+        let clicks = [(0.0, 0.0), (13.0, 26.0), (640.5, 333.3), (999.0, 799.0)];
+        let hits = |view: &ViewState| -> Vec<_> {
+            clicks.iter().map(|&(x, y)| view.codepoint_at(x, y)).collect()
+        };
+
+        // A panned view and a fitted one, each resized the way the explorer does
+        // when the pixel ratio changes
+        let mut panned = view();
+        panned.zoom_around(0.0, 0.0, 1.3);
+        panned.offset_x = -417.0;
+        panned.offset_y = -95.5;
+        let mut fitted = view();
+        fitted.fit(0.0, 0.0);
+        let expected = (hits(&panned), hits(&fitted));
+
+        for ratio in [1.0, 1.25, 1.5, 2.0, 3.0] {
+            let backing = panned.resize(1000.0, 800.0, ratio);
+            assert_eq!(backing, ((1000.0 * ratio) as u32, (800.0 * ratio) as u32));
+            fitted.resize(1000.0, 800.0, ratio);
+            fitted.fit(0.0, 0.0);
+
+            assert_eq!(hits(&panned), expected.0, "panned, ratio {ratio}");
+            assert_eq!(hits(&fitted), expected.1, "fitted, ratio {ratio}");
+            assert_eq!((panned.width, panned.height), (1000.0, 800.0));
+        }
+    }
+
+    #[test]
+    fn numeric_values_outside_nd() {
+        assert_eq!(numeric_value(0x0037).as_deref(), Some("7"));
+        assert_eq!(numeric_value(0x00BD).as_deref(), Some("0.5"));
+        assert_eq!(numeric_value(0x2153).as_deref(), Some("1/3"));
+        assert_eq!(numeric_value(0x216B).as_deref(), Some("12"));
+        assert_eq!(numeric_value(0x217F).as_deref(), Some("1000"));
+        assert_eq!(numeric_value(0x2180).as_deref(), Some("1000"));
+        assert_eq!(numeric_value(0x2181).as_deref(), Some("5000"));
+        assert_eq!(numeric_value(0x4E00).as_deref(), Some("1"));
+        assert_eq!(numeric_value(0x4E8C).as_deref(), Some("2"));
+        assert_eq!(numeric_value(0x4E09).as_deref(), Some("3"));
+        assert_eq!(numeric_value(0x5341).as_deref(), Some("10"));
+        assert_eq!(numeric_value(0x0041), None);
+    }
+
+    #[test]
+    fn heart_ranks_whole_words_above_substrings() {
+        let results = search_characters("heart", 2000, None);
+        let rank = |cp: u32| results.iter().position(|&r| r == cp);
+        let black_heart = rank(0x2764).expect("HEAVY BLACK HEART is a match");
+
+        // Every "heart" inside a longer word (SWEETHEART, HEARTS, ...) comes later
+        let incidental: Vec<u32> = results
+            .iter()
+            .copied()
+            .filter(|&cp| {
+                let name = unicode_names2::name(char::from_u32(cp).unwrap()).unwrap().to_string();
+                !name.split([' ', '-']).any(|word| word == "HEART")
+            })
+            .collect();
+        assert!(!incidental.is_empty());
+        for cp in incidental {
+            assert!(black_heart < rank(cp).unwrap(), "U+{cp:04X} outranks U+2764");
+        }
+    }
+
+    #[test]
+    fn heart_ranked_session_matches_search_characters() {
+        let mut session = SearchSession::ranked("  Heart ", 20);
+        while !session.step(50_000) {}
+        assert_eq!(session.results(), search_characters("heart", 20, None));
+    }
+
+    #[test]
+    fn codepoint_order_session_keeps_substring_semantics() {
+        // Token order matters for the plain session, unlike the ranked one
+        let mut session = SearchSession::new("black heart", 50);
+        while !session.step(MAX_CODEPOINT + 1) {}
+        assert!(session.results().contains(&0x2764));
+        let mut session = SearchSession::new("heart black", 50);
+        while !session.step(MAX_CODEPOINT + 1) {}
+        assert!(session.results().is_empty());
+
+        let sorted = search_from("heart", 0, 10);
+        assert_eq!(sorted.len(), 10);
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn search_from_pages_cover_single_search() {
+        let all = search_from("arrow", 0, 5000);
+        assert!(all.len() < 5000);
+
+        let mut paged = Vec::new();
+        let mut start = 0;
+        loop {
+            let page = search_from("arrow", start, 97);
+            paged.extend_from_slice(&page);
+            if page.len() < 97 {
+                break;
+            }
+            start = page[page.len() - 1] + 1;
+        }
+        assert_eq!(paged, all);
+    }
+
+    #[test]
+    fn legend_follows_color_overrides() {
+        use unic_ucd_category::GeneralCategory;
+
+        let mut overrides = HashMap::new();
+        let defaults = legend_entries(&overrides);
+        assert_eq!(defaults.len(), LEGEND.len());
+        assert_eq!(defaults[0], (COLOR_LETTERS.to_string(), "Letters".to_string()));
+
+        // One category of a group gets its own row under the group
+        overrides.insert(GeneralCategory::UppercaseLetter, "#ff0000".to_string());
+        let entries = legend_entries(&overrides);
+        assert_eq!(entries.len(), LEGEND.len() + 1);
+        assert_eq!(entries[0], (COLOR_LETTERS.to_string(), "Letters".to_string()));
+        assert_eq!(entries[1], ("#ff0000".to_string(), "Uppercase Letter".to_string()));
+
+        // A whole group recolored the same way just changes the group swatch
+        for cat in [
+            GeneralCategory::DecimalNumber,
+            GeneralCategory::LetterNumber,
+            GeneralCategory::OtherNumber,
+        ] {
+            overrides.insert(cat, "#00ff00".to_string());
+        }
+        let entries = legend_entries(&overrides);
+        assert_eq!(entries.len(), LEGEND.len() + 1);
+        assert!(entries.contains(&("#00ff00".to_string(), "Numbers".to_string())));
+        assert!(!entries.iter().any(|(color, _)| color == COLOR_NUMBERS));
+
+        // Unassigned is a single category, so overriding it recolors its row
+        overrides.insert(GeneralCategory::Unassigned, "#123456".to_string());
+        let entries = legend_entries(&overrides);
+        assert_eq!(entries.last().unwrap(), &("#123456".to_string(), "Unassigned".to_string()));
+    }
+
+    #[test]
+    fn out_of_range_center_keeps_plane_stats_valid() {
+        let mut view = view();
+        assert!(view.center_on(0x1F600));
+        assert_eq!(view.plane, 1);
+
+        assert!(!view.center_on(0x110000));
+        assert!(!view.center_on(u32::MAX));
+        assert_eq!(view.plane, 1);
+        assert!(plane_stats_json(view.plane).starts_with("{\"plane\":1,"));
+
+        // Out-of-range planes report nothing rather than indexing past the table
+        assert_eq!(plane_stats_json(17), "{}");
+        assert_eq!(plane_stats_json(u32::MAX), "{}");
+        assert!(plane_category_counts(17).is_empty());
+    }
+
+    #[test]
+    fn glyph_text_isolates_rtl_cells() {
+        // Hebrew and Arabic letters are isolated from the neighbouring cell
+        assert_eq!(glyph_text('\u{05D0}', DEFAULT_MARK_BASE), "\u{2068}\u{05D0}\u{2069}");
+        assert_eq!(glyph_text('\u{0628}', DEFAULT_MARK_BASE), "\u{2068}\u{0628}\u{2069}");
+
+        // Latin letters and neutrals come back unchanged
+        assert_eq!(glyph_text('A', DEFAULT_MARK_BASE), "A");
+        assert_eq!(glyph_text('1', DEFAULT_MARK_BASE), "1");
+        assert_eq!(glyph_text('!', DEFAULT_MARK_BASE), "!");
+
+        // A Hebrew cell next to a Latin one: the isolate closes before the Latin text
+        let row = format!("{}{}", glyph_text('\u{05D0}', DEFAULT_MARK_BASE), glyph_text('A', DEFAULT_MARK_BASE));
+        assert_eq!(row, "\u{2068}\u{05D0}\u{2069}A");
+
+        // Marks still get their base
+        assert_eq!(glyph_text('\u{0301}', 'o'), "o\u{0301}");
+    }
+
+    #[test]
+    fn data_versions_report_east_asian_width_separately() {
+        let versions = data_versions();
+        assert!(versions.contains(&format!("\"ucd\":\"{}\"", unicode_version())));
+        assert!(versions.contains("\"east_asian_width\":\"14.0.0\""));
+    }
+
+    #[test]
+    fn center_offsets_put_cell_in_middle() {
+        let mut view = view();
+        let (x, y) = view.center_offsets(0x4E00);
+        view.offset_x = x;
+        view.offset_y = y;
+        assert_eq!(view.codepoint_at(500.0, 400.0), Some(0x4E00));
+    }
+
+    #[test]
+    fn zoom_around_keeps_anchor_fixed() {
+        let mut view = view();
+        let before = view.codepoint_at(333.0, 222.0);
+        view.zoom_around(333.0, 222.0, 3.5);
+        assert_eq!(view.zoom, 3.5);
+        assert_eq!(view.codepoint_at(333.0, 222.0), before);
+    }
+
+    #[test]
+    fn zoom_around_round_trips_without_drift() {
+        let mut view = view();
+        view.offset_x = -1234.5;
+        view.offset_y = 321.25;
+        let (start_x, start_y) = (view.offset_x, view.offset_y);
+
+        for _ in 0..100 {
+            view.zoom_around(417.0, 293.0, view.zoom * 1.02);
+        }
+        for _ in 0..100 {
+            view.zoom_around(417.0, 293.0, view.zoom / 1.02);
+        }
+
+        // 1.02^100 stays under the 10x clamp, so every step is reversible
+        assert!((view.zoom - 1.0).abs() < 1e-9);
+        assert!((view.offset_x - start_x).abs() < 1e-6, "offset_x drifted to {}", view.offset_x);
+        assert!((view.offset_y - start_y).abs() < 1e-6, "offset_y drifted to {}", view.offset_y);
+    }
+
+    #[test]
+    fn fit_keeps_plane_clear_of_inset() {
+        let mut view = view();
+        view.fit(52.0, 48.0);
+        let cell_size = CELL_SIZE * view.zoom;
+        assert!(view.offset_x >= 52.0 && view.offset_y >= 48.0);
+        assert!(view.offset_x + 256.0 * cell_size <= 1000.0 + 1e-9);
+        assert!(view.offset_y + 256.0 * cell_size <= 800.0 + 1e-9);
+        assert_eq!(view.codepoint_at(view.offset_x, view.offset_y), Some(0x0000));
+    }
+
+    #[test]
+    fn visible_bounds_cover_view() {
+        let mut view = view();
+        assert_eq!(view.visible_bounds(), (0, 0, 50, 40));
+
+        view.offset_x = -30.0;
+        view.offset_y = -50.0;
+        assert_eq!(view.visible_bounds(), (1, 2, 52, 43));
+    }
+
+    #[test]
+    fn visible_bounds_empty_when_panned_off_grid() {
+        let mut view = view();
+        view.offset_x = -1e9;
+        view.offset_y = -1e9;
+        let (start_col, start_row, end_col, end_row) = view.visible_bounds();
+        assert!(start_col <= end_col && start_row <= end_row);
+        assert_eq!((start_col, start_row), (256, 256));
+        assert_eq!((end_col, end_row), (256, 256));
+
+        view.offset_x = 1e9;
+        view.offset_y = 1e9;
+        assert_eq!(view.visible_bounds(), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn scroll_into_view_moves_minimally() {
+        let mut view = view();
+        assert!(!view.scroll_into_view(3, 3));
+
+        // Cell 60 starts at x=1200, so it needs to move 1220 - 1000 to fit
+        assert!(view.scroll_into_view(60, 0));
+        assert_eq!(view.offset_x, -220.0);
+        assert_eq!(view.offset_y, 0.0);
+
+        assert!(view.scroll_into_view(0, 0));
+        assert_eq!(view.offset_x, 0.0);
+    }
 }
@@ -1,4 +1,4 @@
-import init, { UnicodeExplorer, get_char_info, search_characters } from './pkg/unicode_explorer.js';
+import init, { UnicodeExplorer, SearchSession, get_char_info, find_block } from './pkg/unicode_explorer.js';
 
 let explorer = null;
 let animationId = null;
@@ -11,10 +11,14 @@ async function main() {
     const canvas = document.getElementById('canvas');
     const searchInput = document.getElementById('search');
     const planeSelect = document.getElementById('plane-select');
+    const colorModeSelect = document.getElementById('color-mode');
+    const layoutSelect = document.getElementById('layout');
+    const filterSelect = document.getElementById('filter');
     const zoomIn = document.getElementById('zoom-in');
     const zoomOut = document.getElementById('zoom-out');
     const zoomLevel = document.getElementById('zoom-level');
     const copyBtn = document.getElementById('copy-char');
+    const viewDescription = document.getElementById('view-description');
     
     // Info panel elements
     const charDisplay = document.getElementById('char-display');
@@ -22,17 +26,35 @@ async function main() {
     const infoName = document.getElementById('info-name');
     const infoCategory = document.getElementById('info-category');
     const infoBlock = document.getElementById('info-block');
+    const infoAge = document.getElementById('info-age');
+    const infoCcc = document.getElementById('info-ccc');
+    const infoNfd = document.getElementById('info-nfd');
+    const infoNfc = document.getElementById('info-nfc');
+    const infoJamo = document.getElementById('info-jamo');
+    const infoEaw = document.getElementById('info-eaw');
+    const infoLineBreak = document.getElementById('info-linebreak');
+    const infoNumeric = document.getElementById('info-numeric');
+    const infoMirror = document.getElementById('info-mirror');
+    const infoUtf8 = document.getElementById('info-utf8');
+    const infoUtf16 = document.getElementById('info-utf16');
     
     // Initialize explorer
     explorer = new UnicodeExplorer(canvas);
+    explorer.set_pan_bounds(true);
+    explorer.set_show_context(true);
     
-    // Handle resize
+    // Handle resize (sizes in CSS pixels; the explorer scales the backing store)
+    let viewWidth = 0;
+    let viewHeight = 0;
     function resize() {
         const rect = canvas.parentElement.getBoundingClientRect();
         const infoPanel = document.getElementById('info-panel');
-        const width = rect.width - infoPanel.offsetWidth;
-        const height = rect.height;
-        explorer.resize(width, height);
+        viewWidth = rect.width - infoPanel.offsetWidth;
+        viewHeight = rect.height;
+        canvas.style.width = viewWidth + 'px';
+        canvas.style.height = viewHeight + 'px';
+        explorer.set_device_pixel_ratio(window.devicePixelRatio || 1);
+        explorer.resize(viewWidth, viewHeight);
         render();
     }
     
@@ -41,34 +63,65 @@ async function main() {
     
     // Render loop
     function render() {
-        explorer.render();
+        if (explorer.needs_render()) {
+            explorer.render();
+        }
         zoomLevel.textContent = Math.round(explorer.get_zoom() * 100) + '%';
+        const description = explorer.describe_view();
+        if (viewDescription.textContent !== description) {
+            viewDescription.textContent = description;
+        }
     }
     
     // Mouse events
+    // Shift+drag draws a rectangular selection instead of panning
+    let selectingRange = false;
     canvas.addEventListener('mousedown', (e) => {
-        explorer.start_drag(e.offsetX, e.offsetY);
+        selectingRange = e.shiftKey;
+        if (selectingRange) {
+            explorer.start_select(e.offsetX, e.offsetY);
+        } else {
+            explorer.start_drag(e.offsetX, e.offsetY);
+        }
+        render();
     });
     
     canvas.addEventListener('mousemove', (e) => {
         explorer.drag(e.offsetX, e.offsetY);
+        explorer.update_select(e.offsetX, e.offsetY);
+        explorer.set_cursor(e.offsetX, e.offsetY);
+        updateHover(explorer.hover(e.offsetX, e.offsetY));
         render();
     });
     
     canvas.addEventListener('mouseup', () => {
         explorer.end_drag();
+        if (selectingRange) {
+            explorer.end_select();
+            selectingRange = false;
+        }
     });
     
     canvas.addEventListener('mouseleave', () => {
         explorer.end_drag();
+        explorer.clear_hover();
+        explorer.clear_cursor();
+        updateHover(null);
+        render();
     });
     
     canvas.addEventListener('click', (e) => {
-        const codepoint = explorer.click(e.offsetX, e.offsetY);
-        if (codepoint !== undefined && codepoint !== null) {
+        if (e.shiftKey) return;
+        explorer.clear_range_selection();
+        explorer.click(e.offsetX, e.offsetY);
+        render();
+    });
+    
+    // Keep the info panel in sync with any selection change
+    explorer.set_on_select((codepoint) => {
+        if (codepoint !== null) {
             updateInfo(codepoint);
         }
-        render();
     });
     
     // Wheel zoom
@@ -79,53 +132,49 @@ async function main() {
     }, { passive: false });
     
     // Touch events for mobile
-    let touchStartDist = 0;
-    let touchStartZoom = 1;
+    function touchPoints(e) {
+        const rect = canvas.getBoundingClientRect();
+        return [...e.touches].slice(0, 2).flatMap((t) => [t.clientX - rect.left, t.clientY - rect.top]);
+    }
     
     canvas.addEventListener('touchstart', (e) => {
         if (e.touches.length === 1) {
-            const touch = e.touches[0];
-            const rect = canvas.getBoundingClientRect();
-            explorer.start_drag(touch.clientX - rect.left, touch.clientY - rect.top);
+            explorer.start_drag(...touchPoints(e));
         } else if (e.touches.length === 2) {
-            touchStartDist = Math.hypot(
-                e.touches[0].clientX - e.touches[1].clientX,
-                e.touches[0].clientY - e.touches[1].clientY
-            );
-            touchStartZoom = explorer.get_zoom();
+            explorer.end_drag();
+            explorer.pinch_start(...touchPoints(e));
         }
     });
     
     canvas.addEventListener('touchmove', (e) => {
         e.preventDefault();
         if (e.touches.length === 1) {
-            const touch = e.touches[0];
-            const rect = canvas.getBoundingClientRect();
-            explorer.drag(touch.clientX - rect.left, touch.clientY - rect.top);
+            explorer.drag(...touchPoints(e));
             render();
         } else if (e.touches.length === 2) {
-            const dist = Math.hypot(
-                e.touches[0].clientX - e.touches[1].clientX,
-                e.touches[0].clientY - e.touches[1].clientY
-            );
-            const scale = dist / touchStartDist;
-            explorer.set_zoom(touchStartZoom * scale);
+            explorer.pinch_move(...touchPoints(e));
             render();
         }
     }, { passive: false });
     
-    canvas.addEventListener('touchend', () => {
-        explorer.end_drag();
+    canvas.addEventListener('touchend', (e) => {
+        explorer.pinch_end();
+        if (e.touches.length === 1) {
+            // Lifting one finger of a pinch continues as a drag from the other
+            explorer.start_drag(...touchPoints(e));
+        } else {
+            explorer.end_drag();
+        }
     });
     
     // Zoom buttons
     zoomIn.addEventListener('click', () => {
-        explorer.set_zoom(explorer.get_zoom() * 1.2);
+        explorer.zoom_in_step();
         render();
     });
     
     zoomOut.addEventListener('click', () => {
-        explorer.set_zoom(explorer.get_zoom() / 1.2);
+        explorer.zoom_out_step();
         render();
     });
     
@@ -135,36 +184,101 @@ async function main() {
         render();
     });
     
-    // Search
+    // Center on a codepoint and select it
+    function jumpTo(cp) {
+        const plane = Math.floor(cp / 0x10000);
+        planeSelect.value = plane;
+        explorer.set_plane(plane);
+        explorer.center_on(cp);
+        explorer.click(viewWidth / 2, viewHeight / 2);
+        render();
+    }
+    
+    // Enter / Shift+Enter in the search box cycles through results
+    function stepResult(backwards) {
+        const cp = backwards ? explorer.prev_result() : explorer.next_result();
+        if (cp !== undefined && cp !== null) {
+            planeSelect.value = explorer.get_plane();
+            render();
+        }
+    }
+    
+    searchInput.addEventListener('keydown', (e) => {
+        if (e.key === 'Enter') {
+            e.preventDefault();
+            stepResult(e.shiftKey);
+        }
+    });
+    
+    // Color mode select
+    layoutSelect.addEventListener('change', () => {
+        explorer.set_layout(parseInt(layoutSelect.value));
+        render();
+    });
+    
+    colorModeSelect.addEventListener('change', () => {
+        explorer.set_color_mode(parseInt(colorModeSelect.value));
+        render();
+    });
+    
+    filterSelect.addEventListener('change', () => {
+        explorer.set_filter(parseInt(filterSelect.value));
+        render();
+    });
+    
+    // Search (driven across animation frames to keep the page responsive)
     let searchTimeout = null;
+    let searchFrame = null;
+    let searchSession = null;
+    
+    function cancelSearch() {
+        cancelAnimationFrame(searchFrame);
+        if (searchSession) {
+            searchSession.free();
+            searchSession = null;
+        }
+    }
+    
     searchInput.addEventListener('input', () => {
         clearTimeout(searchTimeout);
+        cancelSearch();
+        explorer.set_search_highlights(new Uint32Array());
+        explorer.set_results(new Uint32Array());
+        explorer.highlight_block('');
         searchTimeout = setTimeout(() => {
             const query = searchInput.value.trim();
             if (query.length >= 2) {
-                const results = search_characters(query, 20);
-                // For now, jump to first result
-                if (results.length > 0) {
-                    const cp = results[0];
-                    const plane = Math.floor(cp / 0x10000);
-                    planeSelect.value = plane;
-                    explorer.set_plane(plane);
-                    explorer.center_on(cp);
-                    explorer.click(explorer.width / 2, explorer.height / 2);
-                    updateInfo(cp);
-                    render();
+                // Block names take priority, e.g. "cyrillic" or "emoticons"
+                const blockStart = find_block(query);
+                if (blockStart !== undefined && blockStart !== null) {
+                    explorer.highlight_block(query);
+                    jumpTo(blockStart);
+                    return;
                 }
+                
+                searchSession = SearchSession.ranked(query, 20);
+                const stepSearch = () => {
+                    if (!searchSession.step(20000)) {
+                        searchFrame = requestAnimationFrame(stepSearch);
+                        return;
+                    }
+                    const results = searchSession.results();
+                    cancelSearch();
+                    explorer.set_search_highlights(results);
+                    explorer.set_results(results);
+                    stepResult(false);
+                };
+                stepSearch();
             }
         }, 300);
     });
     
     // Copy button
     copyBtn.addEventListener('click', async () => {
-        const selected = explorer.get_selected();
-        if (selected !== undefined && selected !== null) {
-            const char = String.fromCodePoint(selected);
+        const text = explorer.selected_as_string();
+        if (text.length > 0) {
             try {
-                await navigator.clipboard.writeText(char);
+                await navigator.clipboard.writeText(text);
                 copyBtn.textContent = 'Copied!';
                 setTimeout(() => {
                     copyBtn.textContent = 'Copy Character';
@@ -175,19 +289,44 @@ async function main() {
         }
     });
     
+    // Hover tooltip
+    let lastHovered = null;
+    function updateHover(codepoint) {
+        if (codepoint === undefined) codepoint = null;
+        if (codepoint === lastHovered) return;
+        lastHovered = codepoint;
+        if (codepoint === null) {
+            canvas.title = '';
+            return;
+        }
+        const info = get_char_info(codepoint);
+        canvas.title = `${info.codepoint} ${info.name}`;
+        info.free();
+    }
+    
     // Update info panel
     function updateInfo(codepoint) {
-        try {
-            const info = JSON.parse(get_char_info(codepoint));
-            charDisplay.textContent = info.char ? String.fromCodePoint(codepoint) : '—';
-            infoCodepoint.textContent = info.codepoint;
-            infoName.textContent = info.name;
-            infoCategory.textContent = info.category;
-            infoBlock.textContent = info.block;
-            copyBtn.disabled = !info.char;
-        } catch (err) {
-            console.error('Error getting char info:', err);
-        }
+        const info = get_char_info(codepoint);
+        charDisplay.textContent = info.char ?? '—';
+        infoCodepoint.textContent = info.codepoint;
+        infoName.textContent = info.name;
+        infoCategory.textContent = info.category;
+        infoBlock.textContent = info.block;
+        infoAge.textContent = info.age ? `Unicode ${info.age}` : '—';
+        infoCcc.textContent = info.char ? info.combining_class : '—';
+        infoNfd.textContent = info.nfd.join(' ') || '—';
+        infoNfc.textContent = info.nfc.join(' ') || '—';
+        infoEaw.textContent = info.east_asian_width || '—';
+        infoLineBreak.textContent = info.line_break;
+        infoJamo.textContent = info.jamo.join(' ') || '—';
+        infoNumeric.textContent = info.numeric_value ?? '—';
+        infoMirror.textContent = info.mirror_of !== undefined
+            ? String.fromCodePoint(info.mirror_of)
+            : info.mirrored ? 'Mirrored (no pair)' : '—';
+        infoUtf8.textContent = info.utf8.join(' ') || '—';
+        infoUtf16.textContent = info.utf16.join(' ') || '—';
+        copyBtn.disabled = !info.char;
+        info.free();
     }
     
     // Keyboard shortcuts
@@ -197,21 +336,81 @@ async function main() {
         switch (e.key) {
             case '+':
             case '=':
-                explorer.set_zoom(explorer.get_zoom() * 1.2);
+                explorer.zoom_in_step();
                 render();
                 break;
             case '-':
-                explorer.set_zoom(explorer.get_zoom() / 1.2);
+                explorer.zoom_out_step();
                 render();
                 break;
             case '0':
                 explorer.set_zoom(1);
                 render();
                 break;
+            case 'f':
+                explorer.fit_plane();
+                render();
+                break;
+            case 'r':
+                explorer.random_assigned();
+                planeSelect.value = explorer.get_plane();
+                render();
+                break;
+            case 'x':
+                explorer.set_show_crosshair(!explorer.is_crosshair_visible());
+                render();
+                break;
+            case 'b': {
+                const selected = explorer.get_selected();
+                if (selected !== undefined && !explorer.add_bookmark(selected)) {
+                    explorer.remove_bookmark(selected);
+                }
+                render();
+                break;
+            }
+            case ',':
+                explorer.back();
+                planeSelect.value = explorer.get_plane();
+                render();
+                break;
+            case '.':
+                explorer.forward();
+                planeSelect.value = explorer.get_plane();
+                render();
+                break;
+            case 'PageUp':
+            case 'PageDown':
+                e.preventDefault();
+                explorer.page(e.key === 'PageUp' ? (e.shiftKey ? 2 : 0) : (e.shiftKey ? 3 : 1));
+                render();
+                break;
+            case 'Home':
+                explorer.reset_view(false);
+                render();
+                break;
+            case '[':
+                planeSelect.value = explorer.prev_plane(false);
+                render();
+                break;
+            case ']':
+                planeSelect.value = explorer.next_plane(false);
+                render();
+                break;
             case '/':
                 e.preventDefault();
                 searchInput.focus();
                 break;
+            case 'ArrowLeft':
+            case 'ArrowRight':
+            case 'ArrowUp':
+            case 'ArrowDown': {
+                e.preventDefault();
+                const dx = e.key === 'ArrowLeft' ? -1 : e.key === 'ArrowRight' ? 1 : 0;
+                const dy = e.key === 'ArrowUp' ? -1 : e.key === 'ArrowDown' ? 1 : 0;
+                explorer.move_selection(dx, dy);
+                render();
+                break;
+            }
         }
     });
     